@@ -0,0 +1,251 @@
+use std::{collections::VecDeque, mem};
+
+use crate::{find_number_of_increases, Depth, WindowError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Baseline {
+  Mean,
+  Median,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalyConfig {
+  pub baseline: Baseline,
+  pub lookback: usize,
+  pub threshold: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly<T> {
+  pub line: usize,
+  pub value: T,
+  pub expected: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cleaning {
+  Remove,
+  Interpolate,
+}
+
+fn expected_depth(recent: &VecDeque<f64>, baseline: Baseline) -> f64 {
+  match baseline {
+    Baseline::Mean => recent.iter().sum::<f64>() / recent.len() as f64,
+    Baseline::Median => {
+      let mut sorted: Vec<f64> = recent.iter().copied().collect();
+      sorted.sort_unstable_by(f64::total_cmp);
+      let middle = sorted.len() / 2;
+      if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+      } else {
+        sorted[middle]
+      }
+    }
+  }
+}
+
+// Anomalies are left out of the baseline so a single spike doesn't drag the
+// readings after it out of line as well. A genuine change of level would then
+// flag everything after it, so once `lookback` anomalies in a row agree with
+// each other they're unflagged and become the new baseline. Line numbers start
+// at 1.
+pub fn find_anomalies<T: Depth>(
+  numbers: &[T],
+  config: &AnomalyConfig,
+) -> Result<Vec<Anomaly<T>>, WindowError> {
+  if config.lookback == 0 {
+    return Err(WindowError::Zero);
+  }
+
+  let mut recent = VecDeque::with_capacity(config.lookback);
+  let mut shifted = VecDeque::with_capacity(config.lookback);
+  let mut anomalies = Vec::new();
+
+  for (index, &depth) in numbers.iter().enumerate() {
+    let value = depth.to_f64();
+    if recent.len() == config.lookback {
+      let expected = expected_depth(&recent, config.baseline);
+      if (value - expected).abs() > config.threshold {
+        anomalies.push(Anomaly {
+          line: index + 1,
+          value: depth,
+          expected,
+        });
+        if !shifted.is_empty()
+          && (value - expected_depth(&shifted, config.baseline)).abs() > config.threshold
+        {
+          shifted.clear();
+        }
+        shifted.push_back(value);
+        if shifted.len() == config.lookback {
+          anomalies.truncate(anomalies.len() - shifted.len());
+          recent = mem::take(&mut shifted);
+        }
+        continue;
+      }
+      recent.pop_front();
+    }
+
+    shifted.clear();
+    recent.push_back(value);
+  }
+
+  Ok(anomalies)
+}
+
+// Interpolated integer readings are rounded to the nearest whole depth; anomalies at
+// either end of the sweep take the value of their nearest clean neighbour.
+pub fn clean_readings<T: Depth>(
+  numbers: &[T],
+  anomalies: &[Anomaly<T>],
+  cleaning: Cleaning,
+) -> Vec<T> {
+  let mut flagged = vec![false; numbers.len()];
+  for anomaly in anomalies {
+    flagged[anomaly.line - 1] = true;
+  }
+
+  match cleaning {
+    Cleaning::Remove => numbers
+      .iter()
+      .zip(&flagged)
+      .filter(|(_, &flagged)| !flagged)
+      .map(|(&depth, _)| depth)
+      .collect(),
+    Cleaning::Interpolate => {
+      let clean: Vec<usize> = (0..numbers.len()).filter(|&i| !flagged[i]).collect();
+      if clean.is_empty() {
+        return numbers.to_vec();
+      }
+
+      let mut next_clean = 0;
+      (0..numbers.len())
+        .map(|i| {
+          while next_clean < clean.len() && clean[next_clean] < i {
+            next_clean += 1;
+          }
+          if !flagged[i] {
+            return numbers[i];
+          }
+
+          let before = next_clean.checked_sub(1).map(|n| clean[n]);
+          let after = clean.get(next_clean).copied();
+          match (before, after) {
+            (Some(before), Some(after)) => {
+              let ratio = (i - before) as f64 / (after - before) as f64;
+              let (from, to) = (numbers[before].to_f64(), numbers[after].to_f64());
+              T::from_f64(from + (to - from) * ratio)
+            }
+            (Some(only), None) | (None, Some(only)) => numbers[only],
+            (None, None) => unreachable!("at least one clean reading exists"),
+          }
+        })
+        .collect()
+    }
+  }
+}
+
+pub fn find_cleaned_number_of_increases<T: Depth>(
+  numbers: &[T],
+  window: usize,
+  config: &AnomalyConfig,
+  cleaning: Cleaning,
+) -> Result<u32, WindowError> {
+  let anomalies = find_anomalies(numbers, config)?;
+  find_number_of_increases(&clean_readings(numbers, &anomalies, cleaning), window)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SPIKY: [u32; 8] = [100, 102, 101, 900, 103, 105, 20, 106];
+
+  fn config(baseline: Baseline) -> AnomalyConfig {
+    AnomalyConfig {
+      baseline,
+      lookback: 3,
+      threshold: 50.0,
+    }
+  }
+
+  #[test]
+  fn find_anomalies_with_mean() {
+    let anomalies = find_anomalies(&SPIKY, &config(Baseline::Mean)).unwrap();
+    assert_eq!(
+      anomalies
+        .iter()
+        .map(|a| (a.line, a.value))
+        .collect::<Vec<_>>(),
+      vec![(4, 900), (7, 20)]
+    );
+    assert_eq!(anomalies[0].expected, 101.0);
+  }
+
+  #[test]
+  fn find_anomalies_with_median() {
+    let anomalies = find_anomalies(&SPIKY, &config(Baseline::Median)).unwrap();
+    assert_eq!(anomalies[0].expected, 101.0);
+    assert_eq!(anomalies[1].expected, 103.0);
+  }
+
+  #[test]
+  fn level_change_becomes_the_baseline() {
+    let numbers = [100, 101, 102, 300, 301, 302, 303, 304, 305, 900, 306];
+    let anomalies = find_anomalies(&numbers, &config(Baseline::Mean)).unwrap();
+    assert_eq!(
+      anomalies
+        .iter()
+        .map(|a| (a.line, a.value))
+        .collect::<Vec<_>>(),
+      vec![(10, 900)]
+    );
+    assert_eq!(anomalies[0].expected, 304.0);
+  }
+
+  #[test]
+  fn disagreeing_anomalies_stay_flagged() {
+    let numbers = [100, 101, 102, 300, 500, 700, 103];
+    let anomalies = find_anomalies(&numbers, &config(Baseline::Mean)).unwrap();
+    assert_eq!(
+      anomalies.iter().map(|a| a.line).collect::<Vec<_>>(),
+      vec![4, 5, 6]
+    );
+  }
+
+  #[test]
+  fn clean_readings_remove_and_interpolate() {
+    let anomalies = find_anomalies(&SPIKY, &config(Baseline::Mean)).unwrap();
+    assert_eq!(
+      clean_readings(&SPIKY, &anomalies, Cleaning::Remove),
+      vec![100, 102, 101, 103, 105, 106]
+    );
+    assert_eq!(
+      clean_readings(&SPIKY, &anomalies, Cleaning::Interpolate),
+      vec![100, 102, 101, 102, 103, 105, 106, 106]
+    );
+  }
+
+  #[test]
+  fn cleaned_number_of_increases() {
+    assert_eq!(
+      find_cleaned_number_of_increases(&SPIKY, 1, &config(Baseline::Mean), Cleaning::Remove),
+      Ok(4)
+    );
+  }
+
+  #[test]
+  fn interpolate_float_readings() {
+    let numbers = [1.0, 1.5, 2.0, 40.0, 3.0];
+    let config = AnomalyConfig {
+      baseline: Baseline::Mean,
+      lookback: 2,
+      threshold: 5.0,
+    };
+    let anomalies = find_anomalies(&numbers, &config).unwrap();
+    assert_eq!(
+      clean_readings(&numbers, &anomalies, Cleaning::Interpolate),
+      vec![1.0, 1.5, 2.0, 2.5, 3.0]
+    );
+  }
+}
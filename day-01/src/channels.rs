@@ -0,0 +1,370 @@
+use std::{
+  error::Error,
+  fmt,
+  fs::File,
+  io::{BufRead, BufReader},
+  path::Path,
+  str::FromStr,
+};
+
+use crate::{find_number_of_increases, Depth, WindowError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelSelector {
+  Name(String),
+  Index(usize),
+}
+
+impl FromStr for ChannelSelector {
+  type Err = std::convert::Infallible;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s.trim().parse::<usize>() {
+      Ok(index) => ChannelSelector::Index(index),
+      Err(_) => ChannelSelector::Name(s.trim().to_string()),
+    })
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChannelError {
+  Empty,
+  NoNumericChannels,
+  UnknownChannel(String),
+  IndexOutOfRange {
+    index: usize,
+    columns: usize,
+  },
+  MissingReading {
+    line: usize,
+    column: usize,
+  },
+  InvalidReading {
+    line: usize,
+    column: usize,
+    text: String,
+  },
+}
+
+impl fmt::Display for ChannelError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ChannelError::Empty => write!(f, "sweep has no readings"),
+      ChannelError::NoNumericChannels => write!(f, "sweep has no numeric channels"),
+      ChannelError::UnknownChannel(name) => write!(f, "no channel named {:?}", name),
+      ChannelError::IndexOutOfRange { index, columns } => write!(
+        f,
+        "channel index {} is out of range for {} columns",
+        index, columns
+      ),
+      ChannelError::MissingReading { line, column } => {
+        write!(f, "line {}: missing reading in column {}", line, column)
+      }
+      ChannelError::InvalidReading { line, column, text } => write!(
+        f,
+        "line {}: {:?} in column {} is not a number",
+        line, text, column
+      ),
+    }
+  }
+}
+
+impl Error for ChannelError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelIncreases {
+  pub name: String,
+  pub simple: u32,
+  pub windowed: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Channels<T = u32> {
+  names: Vec<String>,
+  readings: Vec<Vec<T>>,
+}
+
+fn resolve_columns(
+  header: &[String],
+  selectors: &[ChannelSelector],
+) -> Result<Vec<usize>, ChannelError> {
+  selectors
+    .iter()
+    .map(|selector| match selector {
+      ChannelSelector::Name(name) => header
+        .iter()
+        .position(|column| column == name)
+        .ok_or_else(|| ChannelError::UnknownChannel(name.clone())),
+      ChannelSelector::Index(index) if *index < header.len() => Ok(*index),
+      ChannelSelector::Index(index) => Err(ChannelError::IndexOutOfRange {
+        index: *index,
+        columns: header.len(),
+      }),
+    })
+    .collect()
+}
+
+impl<T: Depth + FromStr> Channels<T> {
+  // The first line is treated as a header when a column that's numeric in
+  // the first data row isn't numeric in it, so a headerless sweep can still
+  // start with a timestamp. A lone line is a header if any field isn't a
+  // number. Without a header channels are named by their column index. Without
+  // selectors every column that's numeric in the first row is a channel, so
+  // timestamps and labels are skipped.
+  pub fn from_reader<R: BufRead>(
+    reader: R,
+    selectors: &[ChannelSelector],
+  ) -> Result<Channels<T>, ChannelError> {
+    let mut lines = reader
+      .lines()
+      .map(|line| line.expect("couldn't parse line"))
+      .enumerate()
+      .map(|(idx, line)| (idx + 1, line))
+      .filter(|(_, line)| !line.trim().is_empty())
+      .peekable();
+
+    let numeric = |text: &str| -> Vec<bool> {
+      text
+        .split(',')
+        .map(|field| field.trim().parse::<T>().is_ok())
+        .collect()
+    };
+    let first = lines.next().ok_or(ChannelError::Empty)?;
+    let first_numeric = numeric(&first.1);
+    let has_header = match lines.peek() {
+      Some((_, second)) => numeric(second)
+        .iter()
+        .zip(&first_numeric)
+        .any(|(&data, &first)| data && !first),
+      None => first_numeric.contains(&false),
+    };
+    let fields: Vec<String> = first.1.split(',').map(|f| f.trim().to_string()).collect();
+    let header = if has_header {
+      fields
+    } else {
+      (0..fields.len()).map(|idx| idx.to_string()).collect()
+    };
+    let first_row = if has_header { None } else { Some(first) };
+    let mut lines = first_row.into_iter().chain(lines).peekable();
+
+    let columns = if selectors.is_empty() {
+      let (_, row) = lines.peek().ok_or(ChannelError::Empty)?;
+      let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+      let numeric: Vec<usize> = (0..header.len())
+        .filter(|&column| fields.get(column).is_some_and(|f| f.parse::<T>().is_ok()))
+        .collect();
+      if numeric.is_empty() {
+        return Err(ChannelError::NoNumericChannels);
+      }
+      numeric
+    } else {
+      resolve_columns(&header, selectors)?
+    };
+    let mut readings = vec![Vec::new(); columns.len()];
+    for (line, text) in lines {
+      let fields: Vec<&str> = text.split(',').map(str::trim).collect();
+      for (channel, &column) in columns.iter().enumerate() {
+        let field = fields
+          .get(column)
+          .ok_or(ChannelError::MissingReading { line, column })?;
+        let depth = field
+          .parse::<T>()
+          .map_err(|_| ChannelError::InvalidReading {
+            line,
+            column,
+            text: field.to_string(),
+          })?;
+        readings[channel].push(depth);
+      }
+    }
+
+    if readings[0].is_empty() {
+      return Err(ChannelError::Empty);
+    }
+
+    Ok(Channels {
+      names: columns
+        .iter()
+        .map(|&column| header[column].clone())
+        .collect(),
+      readings,
+    })
+  }
+
+  pub fn from_file(
+    filename: impl AsRef<Path>,
+    selectors: &[ChannelSelector],
+  ) -> Result<Channels<T>, ChannelError> {
+    let file = File::open(filename).expect("file doesn't exist");
+    Channels::from_reader(BufReader::new(file), selectors)
+  }
+
+  pub fn names(&self) -> &[String] {
+    &self.names
+  }
+
+  pub fn readings(&self, name: &str) -> Option<&[T]> {
+    self
+      .names
+      .iter()
+      .position(|channel| channel == name)
+      .map(|idx| self.readings[idx].as_slice())
+  }
+
+  // `windowed` is `None` for a sweep shorter than the window.
+  pub fn increases(&self, window: usize) -> Result<Vec<ChannelIncreases>, WindowError> {
+    self
+      .names
+      .iter()
+      .zip(&self.readings)
+      .map(|(name, readings)| {
+        Ok(ChannelIncreases {
+          name: name.clone(),
+          simple: find_number_of_increases(readings, 1)?,
+          windowed: match find_number_of_increases(readings, window) {
+            Ok(increases) => Some(increases),
+            Err(WindowError::LargerThanInput { .. }) => None,
+            Err(err) => return Err(err),
+          },
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SWEEP: &str = "timestamp,port,starboard,keel
+2021-12-01T00:00,199,10,5
+2021-12-01T00:01,200,9,5
+2021-12-01T00:02,208,8,6
+
+2021-12-01T00:03,210,11,7
+";
+
+  fn selectors(list: &str) -> Vec<ChannelSelector> {
+    list.split(',').map(|s| s.parse().unwrap()).collect()
+  }
+
+  fn parse(input: &str, list: &str) -> Result<Channels, ChannelError> {
+    let selectors = if list.is_empty() {
+      Vec::new()
+    } else {
+      selectors(list)
+    };
+    Channels::from_reader(input.as_bytes(), &selectors)
+  }
+
+  #[test]
+  fn select_channels_by_name_and_index() {
+    let channels = parse(SWEEP, "keel,1").unwrap();
+    assert_eq!(channels.names(), ["keel", "port"]);
+    assert_eq!(channels.readings("port"), Some(&[199, 200, 208, 210][..]));
+    assert_eq!(channels.readings("keel"), Some(&[5, 5, 6, 7][..]));
+  }
+
+  #[test]
+  fn increases_side_by_side() {
+    let channels = parse(SWEEP, "port,starboard,keel").unwrap();
+    assert_eq!(
+      channels.increases(3).unwrap(),
+      vec![
+        ChannelIncreases {
+          name: "port".to_string(),
+          simple: 3,
+          windowed: Some(1)
+        },
+        ChannelIncreases {
+          name: "starboard".to_string(),
+          simple: 1,
+          windowed: Some(1)
+        },
+        ChannelIncreases {
+          name: "keel".to_string(),
+          simple: 2,
+          windowed: Some(1)
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn default_to_numeric_channels() {
+    let channels = parse(SWEEP, "").unwrap();
+    assert_eq!(channels.names(), ["port", "starboard", "keel"]);
+    assert_eq!(channels.readings("timestamp"), None);
+  }
+
+  #[test]
+  fn short_sweeps_have_no_windowed_count() {
+    let channels = parse("port\n1\n2\n", "").unwrap();
+    assert_eq!(
+      channels.increases(3).unwrap(),
+      vec![ChannelIncreases {
+        name: "port".to_string(),
+        simple: 1,
+        windowed: None
+      }]
+    );
+    assert_eq!(channels.increases(0), Err(WindowError::Zero));
+  }
+
+  #[test]
+  fn headerless_input_with_timestamps() {
+    let channels = parse(
+      "2021-12-01T00:00,199,10\n2021-12-01T00:01,200,9\n2021-12-01T00:02,208,8\n",
+      "",
+    )
+    .unwrap();
+    assert_eq!(channels.names(), ["1", "2"]);
+    assert_eq!(channels.readings("1"), Some(&[199, 200, 208][..]));
+  }
+
+  #[test]
+  fn headerless_input_is_indexed() {
+    let channels = parse("1,5\n2,4\n3,6\n", "").unwrap();
+    assert_eq!(channels.names(), ["0", "1"]);
+    assert_eq!(channels.readings("1"), Some(&[5, 4, 6][..]));
+  }
+
+  #[test]
+  fn reports_bad_input() {
+    assert_eq!(
+      parse(SWEEP, "bow"),
+      Err(ChannelError::UnknownChannel("bow".to_string()))
+    );
+    assert_eq!(
+      parse(SWEEP, "7"),
+      Err(ChannelError::IndexOutOfRange {
+        index: 7,
+        columns: 4
+      })
+    );
+    assert_eq!(
+      parse("a,b\n1,2\n3,x\n", "b"),
+      Err(ChannelError::InvalidReading {
+        line: 3,
+        column: 1,
+        text: "x".to_string()
+      })
+    );
+    assert_eq!(
+      parse("a,b\nx,y\n", ""),
+      Err(ChannelError::NoNumericChannels)
+    );
+    assert_eq!(
+      parse("a,b\n1,2\n3\n", "b"),
+      Err(ChannelError::MissingReading { line: 3, column: 1 })
+    );
+  }
+
+  #[test]
+  fn float_channels() {
+    let channels: Channels<f64> =
+      Channels::from_reader("depth,tilt\n-1.5,0.25\n-1.25,0.5\n".as_bytes(), &[]).unwrap();
+    assert_eq!(channels.readings("depth"), Some(&[-1.5, -1.25][..]));
+    assert_eq!(channels.increases(2).unwrap()[1].simple, 1);
+  }
+}
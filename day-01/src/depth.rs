@@ -0,0 +1,78 @@
+use std::{
+  cmp::Ordering,
+  fmt::Debug,
+  iter::Sum,
+  ops::{Add, Sub},
+};
+
+pub trait Depth:
+  Copy + Debug + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Sum
+{
+  fn to_f64(self) -> f64;
+  fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_integer_depth {
+  ($($t:ty),*) => {
+    $(
+      impl Depth for $t {
+        fn to_f64(self) -> f64 {
+          self as f64
+        }
+
+        fn from_f64(value: f64) -> Self {
+          value.round() as $t
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_float_depth {
+  ($($t:ty),*) => {
+    $(
+      impl Depth for $t {
+        fn to_f64(self) -> f64 {
+          self as f64
+        }
+
+        fn from_f64(value: f64) -> Self {
+          value as $t
+        }
+      }
+    )*
+  };
+}
+
+impl_integer_depth!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl_float_depth!(f32, f64);
+
+// Readings within `tolerance` of each other count as unchanged. Readings that
+// can't be ordered at all (NaN) are treated the same way.
+pub fn compare_depths<T: Depth>(previous: T, next: T, tolerance: T) -> Ordering {
+  match next.partial_cmp(&previous) {
+    Some(Ordering::Greater) if next - previous > tolerance => Ordering::Greater,
+    Some(Ordering::Less) if previous - next > tolerance => Ordering::Less,
+    _ => Ordering::Equal,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn compare_integers() {
+    assert_eq!(compare_depths(3u32, 5, 0), Ordering::Greater);
+    assert_eq!(compare_depths(5u32, 3, 0), Ordering::Less);
+    assert_eq!(compare_depths(5u32, 3, 2), Ordering::Equal);
+    assert_eq!(compare_depths(-4i64, -9, 0), Ordering::Less);
+  }
+
+  #[test]
+  fn compare_floats_with_tolerance() {
+    assert_eq!(compare_depths(1.0, 1.0005, 0.001), Ordering::Equal);
+    assert_eq!(compare_depths(1.0, 1.01, 0.001), Ordering::Greater);
+    assert_eq!(compare_depths(1.0, f64::NAN, 0.001), Ordering::Equal);
+  }
+}
@@ -1,41 +1,125 @@
-use std::{
-  fs::File,
-  io::{prelude::*, BufReader},
-  path::Path,
+use std::{cmp::Ordering, error::Error, fmt, fs::File, io::BufReader, path::Path};
+
+mod anomaly;
+mod channels;
+mod depth;
+mod render;
+mod report;
+mod segments;
+mod stream;
+
+pub use anomaly::{
+  clean_readings, find_anomalies, find_cleaned_number_of_increases, Anomaly, AnomalyConfig,
+  Baseline, Cleaning,
+};
+pub use channels::{ChannelError, ChannelIncreases, ChannelSelector, Channels};
+pub use depth::{compare_depths, Depth};
+pub use render::{ascii_plot, sparkline, svg_plot, PlotOptions};
+pub use report::{sweep_report, sweep_report_with_tolerance, Jump, Run, SweepReport};
+pub use segments::{
+  find_segments, find_segments_with_tolerance, write_segments_csv, Segment, Trend,
 };
+pub use stream::{readings_from_reader, IncreaseCounter};
 
 pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<u32> {
   let file = File::open(filename).expect("file doesn't exist");
-  let reader = BufReader::new(file);
-  reader
-    .lines()
-    .map(|line| {
-      line
-        .expect("couldn't parse line")
-        .parse::<u32>()
-        .expect("not a number")
-    })
-    .collect()
+  readings_from_reader::<u32, _>(BufReader::new(file)).collect()
 }
 
-pub fn find_simple_number_of_increases(numbers: Vec<u32>) -> u32 {
-  let mut prev_number: u32 = 0;
-  let mut answer: u32 = 0;
+#[derive(Debug, PartialEq, Eq)]
+pub enum WindowError {
+  Zero,
+  LargerThanInput { window: usize, len: usize },
+}
 
-  for (i, number) in numbers.iter().enumerate() {
-    if i > 0 && *number > prev_number {
-      answer += 1
+impl fmt::Display for WindowError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      WindowError::Zero => write!(f, "window size must be at least 1"),
+      WindowError::LargerThanInput { window, len } => write!(
+        f,
+        "window size {} is larger than the {} readings given",
+        window, len
+      ),
     }
+  }
+}
 
-    prev_number = *number
+impl Error for WindowError {}
+
+fn validate_window<T>(numbers: &[T], window: usize) -> Result<(), WindowError> {
+  if window == 0 {
+    return Err(WindowError::Zero);
+  }
+  if window > numbers.len() {
+    return Err(WindowError::LargerThanInput {
+      window,
+      len: numbers.len(),
+    });
   }
 
-  answer
+  Ok(())
 }
 
-pub fn find_sliding_number_of_increases(numbers: Vec<u32>) -> u32 {
-  let windows = numbers.windows(3).map(|w| w.iter().sum()).collect();
-  find_simple_number_of_increases(windows)
+// Two neighbouring windows share all but their first and last reading, so
+// comparing their sums is the same as comparing `numbers[i]` with `numbers[i - window]`.
+pub fn find_number_of_increases<T: Depth>(
+  numbers: &[T],
+  window: usize,
+) -> Result<u32, WindowError> {
+  find_number_of_increases_with_tolerance(numbers, window, T::default())
+}
+
+pub fn find_number_of_increases_with_tolerance<T: Depth>(
+  numbers: &[T],
+  window: usize,
+  tolerance: T,
+) -> Result<u32, WindowError> {
+  validate_window(numbers, window)?;
+
+  Ok(
+    numbers
+      .iter()
+      .zip(numbers.iter().skip(window))
+      .filter(|(&previous, &number)| {
+        compare_depths(previous, number, tolerance) == Ordering::Greater
+      })
+      .count() as u32,
+  )
+}
+
+pub fn find_summed_number_of_increases<T: Depth>(
+  numbers: &[T],
+  window: usize,
+) -> Result<u32, WindowError> {
+  find_summed_number_of_increases_with_tolerance(numbers, window, T::default())
+}
+
+pub fn find_summed_number_of_increases_with_tolerance<T: Depth>(
+  numbers: &[T],
+  window: usize,
+  tolerance: T,
+) -> Result<u32, WindowError> {
+  validate_window(numbers, window)?;
+
+  let sums: Vec<T> = numbers
+    .windows(window)
+    .map(|w| w.iter().copied().sum())
+    .collect();
+  Ok(
+    sums
+      .windows(2)
+      .filter(|pair| compare_depths(pair[0], pair[1], tolerance) == Ordering::Greater)
+      .count() as u32,
+  )
+}
+
+pub fn find_simple_number_of_increases<T: Depth>(numbers: Vec<T>) -> u32 {
+  find_number_of_increases(&numbers, 1).unwrap_or(0)
+}
+
+pub fn find_sliding_number_of_increases<T: Depth>(numbers: Vec<T>) -> u32 {
+  find_number_of_increases(&numbers, 3).unwrap_or(0)
 }
 
 #[cfg(test)]
@@ -88,4 +172,56 @@ mod tests {
       5
     )
   }
+
+  #[test]
+  fn windowed_matches_simple_and_sliding() {
+    let numbers = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+    assert_eq!(find_number_of_increases(&numbers, 1), Ok(7));
+    assert_eq!(find_number_of_increases(&numbers, 3), Ok(5));
+  }
+
+  #[test]
+  fn windowed_fast_path_matches_summed() {
+    let numbers = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+    for window in 1..=numbers.len() {
+      assert_eq!(
+        find_number_of_increases(&numbers, window),
+        find_summed_number_of_increases(&numbers, window)
+      );
+    }
+  }
+
+  #[test]
+  fn windowed_rejects_invalid_sizes() {
+    assert_eq!(
+      find_number_of_increases(&[1, 2, 3], 0),
+      Err(WindowError::Zero)
+    );
+    assert_eq!(
+      find_number_of_increases(&[1, 2, 3], 4),
+      Err(WindowError::LargerThanInput { window: 4, len: 3 })
+    );
+  }
+
+  #[test]
+  fn windowed_with_signed_depths() {
+    assert_eq!(
+      find_number_of_increases(&[-10i64, -12, -8, -8, -3], 1),
+      Ok(2)
+    );
+  }
+
+  #[test]
+  fn windowed_with_float_tolerance() {
+    let numbers = [10.0, 10.0004, 10.2, 10.2001, 10.1];
+    assert_eq!(find_number_of_increases(&numbers, 1), Ok(3));
+    assert_eq!(
+      find_number_of_increases_with_tolerance(&numbers, 1, 0.001),
+      Ok(1)
+    );
+    assert_eq!(
+      find_summed_number_of_increases_with_tolerance(&numbers, 2, 0.001),
+      find_number_of_increases_with_tolerance(&numbers, 2, 0.001)
+    );
+  }
 }
@@ -1,8 +1,69 @@
-use std::env;
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
+
+    let mut channels: Option<Vec<day_01::ChannelSelector>> = None;
+    let mut plot = false;
+    let mut svg: Option<&String> = None;
+    let mut segments: Option<&String> = None;
+    let mut options = args.iter().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--channels" => {
+                channels = Some(
+                    options
+                        .next()
+                        .map(|list| {
+                            list.split(',')
+                                .map(|s| s.parse().expect("invalid channel"))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                )
+            }
+            "--plot" => plot = true,
+            "--svg" => svg = Some(options.next().expect("missing svg filename")),
+            "--segments" => segments = Some(options.next().expect("missing segments filename")),
+            _ => panic!("unknown option {}", option),
+        }
+    }
+
+    if (filename == "-" || channels.is_some()) && (plot || svg.is_some() || segments.is_some()) {
+        panic!("--plot, --svg and --segments need a depth file");
+    }
+
+    if let Some(selectors) = channels {
+        let channels: day_01::Channels = day_01::Channels::from_file(filename, &selectors)
+            .unwrap_or_else(|err| panic!("couldn't read channels: {}", err));
+        for channel in channels.increases(3).expect("invalid window") {
+            let windowed = channel
+                .windowed
+                .map_or("unavailable".to_string(), |n| n.to_string());
+            println!(
+                "{}: Part 1: {:?} | Part 2: {}",
+                channel.name, channel.simple, windowed
+            );
+        }
+        return;
+    }
+
+    if filename == "-" {
+        let mut simple = day_01::IncreaseCounter::new(1).expect("invalid window");
+        let mut sliding = day_01::IncreaseCounter::new(3).expect("invalid window");
+        for depth in day_01::readings_from_reader::<u32, _>(io::stdin().lock()) {
+            simple.push(depth);
+            sliding.push(depth);
+        }
+        println!("Part 1: {:?}", simple.increases());
+        println!("Part 2: {:?}", sliding.increases());
+        return;
+    }
+
     let lines = day_01::lines_from_file(filename);
     println!(
         "Part 1: {:?}",
@@ -12,4 +73,37 @@ fn main() {
         "Part 2: {:?}",
         day_01::find_sliding_number_of_increases(lines.clone())
     );
+
+    if plot {
+        let options = day_01::PlotOptions {
+            width: 80,
+            height: 20,
+            window: 3,
+            colour: io::stdout().is_terminal(),
+        };
+        print!(
+            "{}",
+            day_01::ascii_plot(&lines, &options).expect("invalid window")
+        );
+    }
+
+    if let Some(path) = svg {
+        let options = day_01::PlotOptions {
+            width: 1200,
+            height: 400,
+            window: 3,
+            colour: true,
+        };
+        fs::write(
+            path,
+            day_01::svg_plot(&lines, &options).expect("invalid window"),
+        )
+        .expect("couldn't write svg");
+    }
+
+    if let Some(path) = segments {
+        let file = fs::File::create(path).expect("couldn't create segments file");
+        day_01::write_segments_csv(io::BufWriter::new(file), &day_01::find_segments(&lines))
+            .expect("couldn't write segments");
+    }
 }
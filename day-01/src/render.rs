@@ -0,0 +1,287 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::{compare_depths, validate_window, Depth, WindowError};
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+const INCREASE_COLOUR: &str = "#2e8b57";
+const DECREASE_COLOUR: &str = "#c0392b";
+const FLAT_COLOUR: &str = "#7f8c8d";
+const WINDOW_COLOUR: &str = "#2c7fb8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlotOptions {
+  pub width: usize,
+  pub height: usize,
+  pub window: usize,
+  // ANSI colours for terminal output; SVG plots are always coloured.
+  pub colour: bool,
+}
+
+fn bounds(values: &[f64]) -> (f64, f64) {
+  values
+    .iter()
+    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+      (min.min(v), max.max(v))
+    })
+}
+
+fn scale(value: f64, (min, max): (f64, f64), steps: usize) -> usize {
+  if max <= min {
+    return 0;
+  }
+
+  (((value - min) / (max - min)) * (steps - 1) as f64).round() as usize
+}
+
+fn colourise(out: &mut String, symbol: char, trend: Ordering, colour: bool) {
+  match (colour, trend) {
+    (true, Ordering::Greater) => write!(out, "{}{}{}", GREEN, symbol, RESET),
+    (true, Ordering::Less) => write!(out, "{}{}{}", RED, symbol, RESET),
+    _ => write!(out, "{}", symbol),
+  }
+  .expect("couldn't write to string");
+}
+
+fn window_sums<T: Depth>(numbers: &[T], window: usize) -> Vec<f64> {
+  numbers
+    .windows(window)
+    .map(|w| w.iter().map(|depth| depth.to_f64()).sum::<f64>())
+    .collect()
+}
+
+fn downsample(values: &[f64], total: usize, columns: usize) -> Vec<Option<f64>> {
+  (0..columns)
+    .map(|column| {
+      let start = column * total / columns;
+      let end = ((column + 1) * total / columns).max(start + 1);
+      let bucket: Vec<f64> = (start..end)
+        .filter_map(|idx| values.get(idx))
+        .copied()
+        .collect();
+      if bucket.is_empty() {
+        None
+      } else {
+        Some(bucket.iter().sum::<f64>() / bucket.len() as f64)
+      }
+    })
+    .collect()
+}
+
+pub fn sparkline<T: Depth>(numbers: &[T], colour: bool) -> String {
+  let values: Vec<f64> = numbers.iter().map(|depth| depth.to_f64()).collect();
+  let range = bounds(&values);
+  let mut out = String::new();
+
+  for (idx, &depth) in numbers.iter().enumerate() {
+    let trend = match idx {
+      0 => Ordering::Equal,
+      _ => compare_depths(numbers[idx - 1], depth, T::default()),
+    };
+    let level = LEVELS[scale(values[idx], range, LEVELS.len())];
+    colourise(&mut out, level, trend, colour);
+  }
+
+  out
+}
+
+// Depth grows downwards, so deeper readings are drawn lower in the plot. When
+// there are more readings than columns each column shows their average. The
+// window sums are scaled to their own range, which the last line gives.
+pub fn ascii_plot<T: Depth>(numbers: &[T], options: &PlotOptions) -> Result<String, WindowError> {
+  validate_window(numbers, options.window)?;
+
+  let values: Vec<f64> = numbers.iter().map(|depth| depth.to_f64()).collect();
+  let columns = options.width.min(numbers.len()).max(1);
+  let height = options.height.max(1);
+  let depths = downsample(&values, numbers.len(), columns);
+  let sums = window_sums(numbers, options.window);
+  let sum_range = bounds(&sums);
+  let range = bounds(&values);
+
+  let mut grid = vec![vec![(' ', Ordering::Equal); columns]; height];
+  for (column, sum) in downsample(&sums, numbers.len(), columns).iter().enumerate() {
+    if let Some(sum) = sum {
+      grid[scale(*sum, sum_range, height)][column] = ('.', Ordering::Equal);
+    }
+  }
+  let mut previous: Option<f64> = None;
+  for (column, depth) in depths.iter().enumerate() {
+    let depth = depth.expect("every column has a reading");
+    let (symbol, trend) = match previous.map(|p| compare_depths(p, depth, 0.0)) {
+      Some(Ordering::Greater) => ('v', Ordering::Greater),
+      Some(Ordering::Less) => ('^', Ordering::Less),
+      _ => ('-', Ordering::Equal),
+    };
+    grid[scale(depth, range, height)][column] = (symbol, trend);
+    previous = Some(depth);
+  }
+
+  let mut out = String::new();
+  for row in grid {
+    for (symbol, trend) in row {
+      colourise(&mut out, symbol, trend, options.colour);
+    }
+    out.push('\n');
+  }
+  writeln!(
+    out,
+    ". window sum, {} at the top to {} at the bottom",
+    sum_range.0, sum_range.1
+  )
+  .expect("couldn't write to string");
+
+  Ok(out)
+}
+
+pub fn svg_plot<T: Depth>(numbers: &[T], options: &PlotOptions) -> Result<String, WindowError> {
+  validate_window(numbers, options.window)?;
+
+  let padding = 20.0;
+  let (width, height) = (options.width as f64, options.height as f64);
+  let values: Vec<f64> = numbers.iter().map(|depth| depth.to_f64()).collect();
+  let (min, max) = bounds(&values);
+  let span = if max > min { max - min } else { 1.0 };
+  let steps = (numbers.len().max(2) - 1) as f64;
+  let x = |idx: usize| padding + idx as f64 / steps * (width - 2.0 * padding);
+  let y = |depth: f64| padding + (depth - min) / span * (height - 2.0 * padding);
+
+  let mut out = String::new();
+  writeln!(
+    out,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+    options.width, options.height, options.width, options.height
+  )
+  .expect("couldn't write to string");
+  writeln!(out, r#"  <rect width="100%" height="100%" fill="white"/>"#)
+    .expect("couldn't write to string");
+
+  for idx in 1..numbers.len() {
+    let colour = match compare_depths(numbers[idx - 1], numbers[idx], T::default()) {
+      Ordering::Greater => INCREASE_COLOUR,
+      Ordering::Less => DECREASE_COLOUR,
+      Ordering::Equal => FLAT_COLOUR,
+    };
+    writeln!(
+      out,
+      r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="1.5"/>"#,
+      x(idx - 1),
+      y(values[idx - 1]),
+      x(idx),
+      y(values[idx]),
+      colour
+    )
+    .expect("couldn't write to string");
+  }
+
+  // The window sums are drawn against a secondary axis on the right.
+  let sums = window_sums(numbers, options.window);
+  let (sum_min, sum_max) = bounds(&sums);
+  let sum_span = if sum_max > sum_min {
+    sum_max - sum_min
+  } else {
+    1.0
+  };
+  let sum_y = |sum: f64| padding + (sum - sum_min) / sum_span * (height - 2.0 * padding);
+  let axis = width - padding;
+  writeln!(
+    out,
+    r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="1"/>"#,
+    axis,
+    padding,
+    axis,
+    height - padding,
+    WINDOW_COLOUR
+  )
+  .expect("couldn't write to string");
+  for (sum, baseline) in [(sum_min, "text-before-edge"), (sum_max, "text-after-edge")] {
+    writeln!(
+      out,
+      r#"  <text x="{:.2}" y="{:.2}" fill="{}" font-size="10" text-anchor="end" dominant-baseline="{}">{}</text>"#,
+      axis - 4.0,
+      sum_y(sum),
+      WINDOW_COLOUR,
+      baseline,
+      sum
+    )
+    .expect("couldn't write to string");
+  }
+
+  let points: Vec<String> = sums
+    .iter()
+    .enumerate()
+    .map(|(idx, &sum)| format!("{:.2},{:.2}", x(idx), sum_y(sum)))
+    .collect();
+  writeln!(
+    out,
+    r#"  <polyline points="{}" fill="none" stroke="{}" stroke-width="1" stroke-dasharray="4 2"/>"#,
+    points.join(" "),
+    WINDOW_COLOUR
+  )
+  .expect("couldn't write to string");
+  writeln!(out, "</svg>").expect("couldn't write to string");
+
+  Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+  #[test]
+  fn sparkline_example() {
+    assert_eq!(sparkline(&EXAMPLE, false), "▁▁▂▂▁▂▅█▇▇");
+    assert_eq!(
+      sparkline(&[1, 2, 1], true),
+      "▁\x1b[32m█\x1b[0m\x1b[31m▁\x1b[0m"
+    );
+  }
+
+  #[test]
+  fn ascii_plot_marks_trends_and_window() {
+    let options = PlotOptions {
+      width: 80,
+      height: 3,
+      window: 2,
+      colour: false,
+    };
+    assert_eq!(
+      ascii_plot(&[0, 10, 0, 0], &options).unwrap(),
+      "- ^-\n    \n.v  \n. window sum, 0 at the top to 10 at the bottom\n"
+    );
+  }
+
+  #[test]
+  fn svg_plot_has_segments_and_overlay() {
+    let options = PlotOptions {
+      width: 200,
+      height: 100,
+      window: 3,
+      colour: false,
+    };
+    let svg = svg_plot(&EXAMPLE, &options).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches(INCREASE_COLOUR).count(), 7);
+    assert_eq!(svg.matches(DECREASE_COLOUR).count(), 2);
+    assert_eq!(svg.matches("<polyline").count(), 1);
+    assert!(svg.contains(">607</text>"));
+    assert!(svg.contains(">792</text>"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+  }
+
+  #[test]
+  fn plots_validate_window() {
+    let options = PlotOptions {
+      width: 10,
+      height: 10,
+      window: 0,
+      colour: false,
+    };
+    assert_eq!(svg_plot(&EXAMPLE, &options), Err(WindowError::Zero));
+  }
+}
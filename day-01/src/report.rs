@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+
+use crate::{compare_depths, validate_window, Depth, WindowError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Run {
+  pub fn length(&self) -> usize {
+    self.end - self.start + 1
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump<T> {
+  pub index: usize,
+  pub amount: T,
+}
+
+// In windowed mode every index refers to the window starting at that reading
+// and every depth is the sum of that window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepReport<T> {
+  pub window: usize,
+  pub increases: u32,
+  pub decreases: u32,
+  pub unchanged: u32,
+  pub longest_increasing_run: Run,
+  pub largest_rise: Option<Jump<T>>,
+  pub largest_fall: Option<Jump<T>>,
+  pub min_depth: T,
+  pub max_depth: T,
+}
+
+impl<T: Depth> SweepReport<T> {
+  fn new(window: usize, first: T) -> SweepReport<T> {
+    SweepReport {
+      window,
+      increases: 0,
+      decreases: 0,
+      unchanged: 0,
+      longest_increasing_run: Run { start: 0, end: 0 },
+      largest_rise: None,
+      largest_fall: None,
+      min_depth: first,
+      max_depth: first,
+    }
+  }
+}
+
+fn record_jump<T: Depth>(largest: &mut Option<Jump<T>>, index: usize, amount: T) {
+  if largest.is_none_or(|jump| amount > jump.amount) {
+    *largest = Some(Jump { index, amount });
+  }
+}
+
+pub fn sweep_report<T: Depth>(numbers: &[T], window: usize) -> Result<SweepReport<T>, WindowError> {
+  sweep_report_with_tolerance(numbers, window, T::default())
+}
+
+pub fn sweep_report_with_tolerance<T: Depth>(
+  numbers: &[T],
+  window: usize,
+  tolerance: T,
+) -> Result<SweepReport<T>, WindowError> {
+  validate_window(numbers, window)?;
+
+  let mut sum: T = numbers[..window].iter().copied().sum();
+  let mut report = SweepReport::new(window, sum);
+  let mut run_start = 0;
+
+  for (index, (leaving, entering)) in numbers.iter().zip(&numbers[window..]).enumerate() {
+    let previous = sum;
+    sum = sum - *leaving + *entering;
+    let index = index + 1;
+
+    match compare_depths(previous, sum, tolerance) {
+      Ordering::Greater => {
+        report.increases += 1;
+        record_jump(&mut report.largest_rise, index, sum - previous);
+      }
+      Ordering::Less => {
+        report.decreases += 1;
+        record_jump(&mut report.largest_fall, index, previous - sum);
+        run_start = index;
+      }
+      Ordering::Equal => {
+        report.unchanged += 1;
+        run_start = index;
+      }
+    }
+
+    if index - run_start + 1 > report.longest_increasing_run.length() {
+      report.longest_increasing_run = Run {
+        start: run_start,
+        end: index,
+      };
+    }
+    if sum < report.min_depth {
+      report.min_depth = sum;
+    }
+    if sum > report.max_depth {
+      report.max_depth = sum;
+    }
+  }
+
+  Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+  #[test]
+  fn simple_report_example() {
+    assert_eq!(
+      sweep_report(&EXAMPLE, 1),
+      Ok(SweepReport {
+        window: 1,
+        increases: 7,
+        decreases: 2,
+        unchanged: 0,
+        longest_increasing_run: Run { start: 0, end: 3 },
+        largest_rise: Some(Jump {
+          index: 6,
+          amount: 33
+        }),
+        largest_fall: Some(Jump {
+          index: 4,
+          amount: 10
+        }),
+        min_depth: 199,
+        max_depth: 269,
+      })
+    );
+  }
+
+  #[test]
+  fn sliding_report_example() {
+    let report = sweep_report(&EXAMPLE, 3).unwrap();
+    assert_eq!(report.increases, 5);
+    assert_eq!(report.decreases, 1);
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.longest_increasing_run, Run { start: 3, end: 7 });
+    assert_eq!(report.min_depth, 607);
+    assert_eq!(report.max_depth, 792);
+  }
+
+  #[test]
+  fn single_reading_report() {
+    let report = sweep_report(&[5], 1).unwrap();
+    assert_eq!(report.longest_increasing_run.length(), 1);
+    assert_eq!(report.largest_rise, None);
+    assert_eq!(report.min_depth, 5);
+  }
+
+  #[test]
+  fn float_report_with_tolerance() {
+    let report = sweep_report_with_tolerance(&[2.5, 2.5001, 3.0, -1.0], 1, 0.001).unwrap();
+    assert_eq!(report.increases, 1);
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.decreases, 1);
+    assert_eq!(report.longest_increasing_run, Run { start: 1, end: 2 });
+    assert_eq!(report.min_depth, -1.0);
+    assert_eq!(
+      report.largest_fall,
+      Some(Jump {
+        index: 3,
+        amount: 4.0
+      })
+    );
+  }
+}
@@ -0,0 +1,169 @@
+use std::{
+  cmp::Ordering,
+  fmt,
+  io::{self, Write},
+};
+
+use crate::{compare_depths, Depth};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+  Rising,
+  Falling,
+  Flat,
+}
+
+impl fmt::Display for Trend {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Trend::Rising => write!(f, "rising"),
+      Trend::Falling => write!(f, "falling"),
+      Trend::Flat => write!(f, "flat"),
+    }
+  }
+}
+
+// Neighbouring segments share their boundary reading, so `end` of one segment
+// is `start` of the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<T> {
+  pub trend: Trend,
+  pub start: usize,
+  pub end: usize,
+  pub start_depth: T,
+  pub end_depth: T,
+}
+
+impl<T: Depth> Segment<T> {
+  pub fn length(&self) -> usize {
+    self.end - self.start + 1
+  }
+
+  pub fn change(&self) -> f64 {
+    self.end_depth.to_f64() - self.start_depth.to_f64()
+  }
+}
+
+pub fn find_segments<T: Depth>(numbers: &[T]) -> Vec<Segment<T>> {
+  find_segments_with_tolerance(numbers, T::default())
+}
+
+pub fn find_segments_with_tolerance<T: Depth>(numbers: &[T], tolerance: T) -> Vec<Segment<T>> {
+  let mut segments: Vec<Segment<T>> = Vec::new();
+  if numbers.len() == 1 {
+    segments.push(Segment {
+      trend: Trend::Flat,
+      start: 0,
+      end: 0,
+      start_depth: numbers[0],
+      end_depth: numbers[0],
+    });
+  }
+
+  for (idx, pair) in numbers.windows(2).enumerate() {
+    let trend = match compare_depths(pair[0], pair[1], tolerance) {
+      Ordering::Greater => Trend::Rising,
+      Ordering::Less => Trend::Falling,
+      Ordering::Equal => Trend::Flat,
+    };
+
+    match segments.last_mut() {
+      Some(segment) if segment.trend == trend => {
+        segment.end = idx + 1;
+        segment.end_depth = pair[1];
+      }
+      _ => segments.push(Segment {
+        trend,
+        start: idx,
+        end: idx + 1,
+        start_depth: pair[0],
+        end_depth: pair[1],
+      }),
+    }
+  }
+
+  segments
+}
+
+pub fn write_segments_csv<T: Depth + fmt::Display, W: Write>(
+  mut writer: W,
+  segments: &[Segment<T>],
+) -> io::Result<()> {
+  writeln!(
+    writer,
+    "trend,start,end,length,start_depth,end_depth,change"
+  )?;
+  for segment in segments {
+    writeln!(
+      writer,
+      "{},{},{},{},{},{},{}",
+      segment.trend,
+      segment.start,
+      segment.end,
+      segment.length(),
+      segment.start_depth,
+      segment.end_depth,
+      segment.change()
+    )?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+  #[test]
+  fn segments_example() {
+    let segments = find_segments(&EXAMPLE);
+    assert_eq!(
+      segments
+        .iter()
+        .map(|s| (s.trend, s.start, s.end, s.change()))
+        .collect::<Vec<_>>(),
+      vec![
+        (Trend::Rising, 0, 3, 11.0),
+        (Trend::Falling, 3, 4, -10.0),
+        (Trend::Rising, 4, 7, 69.0),
+        (Trend::Falling, 7, 8, -9.0),
+        (Trend::Rising, 8, 9, 3.0),
+      ]
+    );
+    assert_eq!(segments[2].length(), 4);
+  }
+
+  #[test]
+  fn segments_with_flat_stretches() {
+    let segments = find_segments_with_tolerance(&[1.0, 1.01, 0.99, 3.0, 3.0], 0.05);
+    assert_eq!(
+      segments
+        .iter()
+        .map(|s| (s.trend, s.start, s.end))
+        .collect::<Vec<_>>(),
+      vec![
+        (Trend::Flat, 0, 2),
+        (Trend::Rising, 2, 3),
+        (Trend::Flat, 3, 4)
+      ]
+    );
+  }
+
+  #[test]
+  fn segments_of_short_sweeps() {
+    assert_eq!(find_segments::<u32>(&[]), vec![]);
+    assert_eq!(find_segments(&[7])[0].length(), 1);
+  }
+
+  #[test]
+  fn segments_as_csv() {
+    let mut csv = Vec::new();
+    write_segments_csv(&mut csv, &find_segments(&[5, 8, 2])).unwrap();
+    assert_eq!(
+      String::from_utf8(csv).unwrap(),
+      "trend,start,end,length,start_depth,end_depth,change\nrising,0,1,2,5,8,3\nfalling,1,2,2,8,2,-6\n"
+    );
+  }
+}
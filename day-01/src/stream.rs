@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+use std::{cmp::Ordering, io::BufRead, str::FromStr};
+
+use crate::{compare_depths, Depth, WindowError};
+
+pub fn readings_from_reader<T: FromStr, R: BufRead>(reader: R) -> impl Iterator<Item = T> {
+  reader.lines().map(
+    |line| match line.expect("couldn't parse line").parse::<T>() {
+      Ok(depth) => depth,
+      Err(_) => panic!("not a number"),
+    },
+  )
+}
+
+#[derive(Debug, Clone)]
+pub struct IncreaseCounter<T: Depth> {
+  window: usize,
+  tolerance: T,
+  recent: VecDeque<T>,
+  increases: u64,
+  readings: u64,
+}
+
+impl<T: Depth> IncreaseCounter<T> {
+  pub fn new(window: usize) -> Result<IncreaseCounter<T>, WindowError> {
+    IncreaseCounter::with_tolerance(window, T::default())
+  }
+
+  pub fn with_tolerance(window: usize, tolerance: T) -> Result<IncreaseCounter<T>, WindowError> {
+    if window == 0 {
+      return Err(WindowError::Zero);
+    }
+
+    Ok(IncreaseCounter {
+      window,
+      tolerance,
+      recent: VecDeque::with_capacity(window),
+      increases: 0,
+      readings: 0,
+    })
+  }
+
+  pub fn push(&mut self, depth: T) {
+    if self.recent.len() == self.window {
+      let oldest = self.recent.pop_front().expect("window is empty");
+      if compare_depths(oldest, depth, self.tolerance) == Ordering::Greater {
+        self.increases += 1;
+      }
+    }
+
+    self.recent.push_back(depth);
+    self.readings += 1;
+  }
+
+  pub fn increases(&self) -> u64 {
+    self.increases
+  }
+
+  pub fn readings(&self) -> u64 {
+    self.readings
+  }
+
+  pub fn window(&self) -> usize {
+    self.window
+  }
+}
+
+impl<T: Depth> Extend<T> for IncreaseCounter<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, readings: I) {
+    for depth in readings {
+      self.push(depth);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::find_number_of_increases;
+
+  const EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+  #[test]
+  fn counter_rejects_zero_window() {
+    assert_eq!(
+      IncreaseCounter::<u32>::new(0).unwrap_err(),
+      WindowError::Zero
+    );
+  }
+
+  #[test]
+  fn counter_from_reader_example() {
+    let mut simple = IncreaseCounter::new(1).unwrap();
+    let mut sliding = IncreaseCounter::new(3).unwrap();
+    for depth in readings_from_reader::<u32, _>(EXAMPLE.as_bytes()) {
+      simple.push(depth);
+      sliding.push(depth);
+    }
+
+    assert_eq!(simple.increases(), 7);
+    assert_eq!(sliding.increases(), 5);
+    assert_eq!(sliding.readings(), 10);
+  }
+
+  #[test]
+  fn counter_reports_running_count() {
+    let mut counter = IncreaseCounter::new(3).unwrap();
+    counter.extend([199, 200, 208, 210]);
+    assert_eq!(counter.increases(), 1);
+    counter.extend([200, 207]);
+    assert_eq!(counter.increases(), 1);
+    counter.extend([240, 269, 260, 263]);
+    assert_eq!(counter.increases(), 5);
+  }
+
+  #[test]
+  fn counter_matches_slice_count() {
+    let numbers = vec![4, 2, 1, 3, 5, 9, 9, 2, 7, 8, 1];
+    for window in 1..=numbers.len() {
+      let mut counter = IncreaseCounter::new(window).unwrap();
+      counter.extend(numbers.iter().copied());
+      assert_eq!(
+        counter.increases(),
+        u64::from(find_number_of_increases(&numbers, window).unwrap())
+      );
+    }
+  }
+
+  #[test]
+  fn counter_with_float_readings() {
+    let mut counter = IncreaseCounter::with_tolerance(1, 0.01).unwrap();
+    counter.extend(readings_from_reader::<f64, _>(
+      "1.5\n1.505\n1.75\n-2.0\n".as_bytes(),
+    ));
+    assert_eq!(counter.increases(), 1);
+  }
+}
@@ -0,0 +1,288 @@
+use std::{fmt, num::NonZeroUsize, thread};
+
+use crate::{Command, Position, SteeringModel, Trajectory};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submarine {
+  pub name: String,
+  pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloseApproach {
+  pub first: String,
+  pub second: String,
+  pub step: usize,
+  pub distance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetRun {
+  names: Vec<String>,
+  trajectories: Vec<Trajectory>,
+  failed: Vec<String>,
+}
+
+// Each squared difference fits in 128 bits but their sum might not, and any
+// saturated sum is further apart than a distance given as an `i64`.
+fn squared_distance(a: &Position, b: &Position) -> u128 {
+  [
+    (a.horizontal, b.horizontal),
+    (a.depth, b.depth),
+    (a.lateral, b.lateral),
+  ]
+  .iter()
+  .map(|&(a, b)| (i128::from(a) - i128::from(b)).unsigned_abs().pow(2))
+  .fold(0, u128::saturating_add)
+}
+
+impl FleetRun {
+  pub fn trajectory(&self, name: &str) -> Option<&Trajectory> {
+    self
+      .names
+      .iter()
+      .position(|n| n == name)
+      .map(|idx| &self.trajectories[idx])
+  }
+
+  // Submarines whose position overflowed; they're left out of everything else.
+  pub fn failed(&self) -> &[String] {
+    &self.failed
+  }
+
+  pub fn final_positions(&self) -> Vec<(&str, Position)> {
+    self
+      .names
+      .iter()
+      .zip(&self.trajectories)
+      .map(|(name, trajectory)| (name.as_str(), trajectory.last()))
+      .collect()
+  }
+
+  // Reports the closest approach of every pair of submarines that come within
+  // `within` of each other at the same step. Every submarine starts at the
+  // origin, so step 0 is skipped; submarines that run out of commands stay
+  // where they stopped.
+  pub fn close_approaches(&self, within: i64) -> Vec<CloseApproach> {
+    let limit = u128::from(within.unsigned_abs()).pow(2);
+    let steps = self
+      .trajectories
+      .iter()
+      .map(|t| t.positions().len())
+      .max()
+      .unwrap_or(0);
+    let at = |trajectory: &Trajectory, step: usize| {
+      let positions = trajectory.positions();
+      positions[step.min(positions.len() - 1)]
+    };
+
+    let mut approaches = Vec::new();
+    for (i, first) in self.trajectories.iter().enumerate() {
+      for (j, second) in self.trajectories.iter().enumerate().skip(i + 1) {
+        let closest = (1..steps)
+          .map(|step| (step, squared_distance(&at(first, step), &at(second, step))))
+          .min_by_key(|&(_, distance)| distance);
+        if let Some((step, distance)) = closest.filter(|&(_, distance)| distance <= limit) {
+          approaches.push(CloseApproach {
+            first: self.names[i].clone(),
+            second: self.names[j].clone(),
+            step,
+            distance: (distance as f64).sqrt(),
+          });
+        }
+      }
+    }
+
+    approaches
+  }
+}
+
+impl fmt::Display for FleetRun {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let width = self
+      .names
+      .iter()
+      .chain(&self.failed)
+      .map(String::len)
+      .max()
+      .unwrap_or(0)
+      .max(4);
+    writeln!(
+      f,
+      "{:<width$} {:>12} {:>12} {:>12}",
+      "name",
+      "horizontal",
+      "depth",
+      "lateral",
+      width = width
+    )?;
+    for (name, position) in self.final_positions() {
+      writeln!(
+        f,
+        "{:<width$} {:>12} {:>12} {:>12}",
+        name,
+        position.horizontal,
+        position.depth,
+        position.lateral,
+        width = width
+      )?;
+    }
+    for name in &self.failed {
+      writeln!(f, "{:<width$} overflowed", name, width = width)?;
+    }
+
+    Ok(())
+  }
+}
+
+// Submarines are split into one chunk per available core.
+pub fn simulate_fleet<M: SteeringModel + Sync>(model: &M, submarines: &[Submarine]) -> FleetRun {
+  let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+  let chunk = submarines.len().div_ceil(workers).max(1);
+  let trajectories: Vec<Option<Trajectory>> = thread::scope(|scope| {
+    let handles: Vec<_> = submarines
+      .chunks(chunk)
+      .map(|chunk| {
+        scope.spawn(move || {
+          chunk
+            .iter()
+            .map(|submarine| Trajectory::try_record(model, &submarine.commands))
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+    handles
+      .into_iter()
+      .flat_map(|handle| handle.join().expect("submarine simulation panicked"))
+      .collect()
+  });
+
+  let mut run = FleetRun {
+    names: Vec::new(),
+    trajectories: Vec::new(),
+    failed: Vec::new(),
+  };
+  for (submarine, trajectory) in submarines.iter().zip(trajectories) {
+    match trajectory {
+      Some(trajectory) => {
+        run.names.push(submarine.name.clone());
+        run.trajectories.push(trajectory);
+      }
+      None => run.failed.push(submarine.name.clone()),
+    }
+  }
+
+  run
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{AimSteering, NaiveSteering};
+
+  fn fleet() -> Vec<Submarine> {
+    vec![
+      Submarine {
+        name: "alpha".to_string(),
+        commands: vec![Command::Forward(5), Command::Down(5), Command::Forward(5)],
+      },
+      Submarine {
+        name: "bravo".to_string(),
+        commands: vec![
+          Command::Down(1),
+          Command::Forward(10),
+          Command::Starboard(2),
+        ],
+      },
+      Submarine {
+        name: "charlie".to_string(),
+        commands: vec![Command::Starboard(100)],
+      },
+    ]
+  }
+
+  #[test]
+  fn fleet_final_positions() {
+    let run = simulate_fleet(&AimSteering, &fleet());
+    assert_eq!(
+      run
+        .final_positions()
+        .into_iter()
+        .map(|(name, p)| (name, p.horizontal, p.depth))
+        .collect::<Vec<_>>(),
+      vec![("alpha", 10, 25), ("bravo", 10, 10), ("charlie", 0, 0)]
+    );
+    assert_eq!(run.trajectory("bravo").unwrap().positions().len(), 4);
+  }
+
+  #[test]
+  fn fleet_close_approaches() {
+    let run = simulate_fleet(&NaiveSteering, &fleet());
+    assert_eq!(
+      run.close_approaches(5),
+      vec![CloseApproach {
+        first: "alpha".to_string(),
+        second: "bravo".to_string(),
+        step: 3,
+        distance: 20f64.sqrt(),
+      }]
+    );
+    assert_eq!(run.close_approaches(200).len(), 3);
+  }
+
+  #[test]
+  fn overflowing_submarines_fail() {
+    let mut submarines = fleet();
+    submarines.push(Submarine {
+      name: "delta".to_string(),
+      commands: vec![
+        Command::Down(i32::MAX),
+        Command::Forward(i32::MAX),
+        Command::Forward(i32::MAX),
+        Command::Forward(i32::MAX),
+      ],
+    });
+    let run = simulate_fleet(&AimSteering, &submarines);
+    assert_eq!(run.failed(), ["delta"]);
+    assert_eq!(run.final_positions().len(), 3);
+    assert!(run.to_string().ends_with("delta   overflowed\n"));
+  }
+
+  #[test]
+  fn distance_between_extreme_depths() {
+    let deep = Position {
+      depth: 4_600_000_000_000_000_000,
+      ..Position::default()
+    };
+    let high = Position {
+      depth: -9_200_000_000_000_000_000,
+      ..Position::default()
+    };
+    assert_eq!(
+      squared_distance(&deep, &high),
+      13_800_000_000_000_000_000u128.pow(2)
+    );
+    let corner = Position {
+      horizontal: i64::MAX,
+      depth: i64::MAX,
+      lateral: i64::MAX,
+      ..Position::default()
+    };
+    let opposite = Position {
+      horizontal: i64::MIN,
+      depth: i64::MIN,
+      lateral: i64::MIN,
+      ..Position::default()
+    };
+    assert_eq!(squared_distance(&corner, &opposite), u128::MAX);
+  }
+
+  #[test]
+  fn fleet_table() {
+    let run = simulate_fleet(&NaiveSteering, &fleet()[..1]);
+    assert_eq!(
+      run.to_string(),
+      "name    horizontal        depth      lateral\nalpha           10            5            0\n"
+    );
+  }
+}
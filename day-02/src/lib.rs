@@ -1,68 +1,236 @@
-use std::{
-  fs::File,
-  io::{prelude::*, BufReader},
-  path::Path,
+use std::{fs::File, io::BufReader, path::Path, str::FromStr};
+
+mod fleet;
+mod optimize;
+mod parse;
+mod planner;
+mod safety;
+mod script;
+mod trajectory;
+
+pub use fleet::{simulate_fleet, CloseApproach, FleetRun, Submarine};
+pub use optimize::{Optimized, Optimizer};
+pub use parse::{
+  parse_all_commands, parse_command, parse_commands, ParseError, ParseErrorKind, DIRECTIONS,
 };
+pub use planner::{RouteError, RoutePlanner, Target};
+pub use safety::{simulate, SafetyEvent, SafetyLimits, Simulation};
+pub use script::{compile_script, script_from_file, ScriptError, ScriptErrorKind};
+pub use trajectory::{trajectories_svg, write_trajectories_csv, Trajectory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  Up,
+  Down,
+  Forward,
+  Port,
+  Starboard,
+}
+
+impl Direction {
+  pub const ALL: [Direction; 5] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Forward,
+    Direction::Port,
+    Direction::Starboard,
+  ];
+
+  pub const fn name(self) -> &'static str {
+    match self {
+      Direction::Up => "up",
+      Direction::Down => "down",
+      Direction::Forward => "forward",
+      Direction::Port => "port",
+      Direction::Starboard => "starboard",
+    }
+  }
+
+  pub fn command(self, distance: i32) -> Command {
+    match self {
+      Direction::Up => Command::Up(distance),
+      Direction::Down => Command::Down(distance),
+      Direction::Forward => Command::Forward(distance),
+      Direction::Port => Command::Port(distance),
+      Direction::Starboard => Command::Starboard(distance),
+    }
+  }
+}
+
+impl FromStr for Direction {
+  type Err = ParseErrorKind;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Direction::ALL
+      .into_iter()
+      .find(|direction| direction.name() == s)
+      .ok_or_else(|| ParseErrorKind::UnknownDirection(s.to_string()))
+  }
+}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Command {
   Up(i32),
   Down(i32),
   Forward(i32),
+  Port(i32),
+  Starboard(i32),
+}
+
+impl Command {
+  pub fn from_direction(direction: &str, distance: i32) -> Option<Command> {
+    direction
+      .parse::<Direction>()
+      .ok()
+      .map(|direction| direction.command(distance))
+  }
 }
 
 pub fn commands_from_file(filename: impl AsRef<Path>) -> Vec<Command> {
   let file = File::open(filename).expect("file doesn't exist");
-  let reader = BufReader::new(file);
-  reader
-    .lines()
-    .map(|line| {
-      let l = line.expect("couldn't parse line");
-      let mut parts = l.split_whitespace();
-      let direction = parts.next().expect("couldn't parse direction");
-      let distance = parts
-        .next()
-        .expect("couldn't parse distance")
-        .parse::<i32>()
-        .expect("not a number");
-
-      match direction {
-        "up" => Command::Up(distance),
-        "down" => Command::Down(distance),
-        "forward" => Command::Forward(distance),
-        _ => panic!("unknown direction"),
-      }
-    })
-    .collect()
+  parse_commands(BufReader::new(file)).unwrap_or_else(|err| panic!("{}", err))
 }
 
-pub fn find_position(commands: &[Command]) -> i32 {
-  let mut position: (i32, i32) = (0, 0);
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+  pub horizontal: i64,
+  pub depth: i64,
+  pub aim: i64,
+  pub lateral: i64,
+  pub drift: i64,
+}
 
-  for command in commands {
-    match command {
-      Command::Up(direction) => position.1 -= direction,
-      Command::Down(direction) => position.1 += direction,
-      Command::Forward(direction) => position.0 += direction,
-    }
+impl Position {
+  pub fn product(&self) -> i128 {
+    i128::from(self.horizontal) * i128::from(self.depth)
   }
-  position.0 * position.1
 }
 
-pub fn find_position_with_aim(commands: &[Command]) -> i32 {
-  let mut position: (i32, i32, i32) = (0, 0, 0);
+// Steps return `None` when the new position doesn't fit in 64 bits. `lateral`
+// grows to starboard and shrinks to port. `run` panics on overflow, where
+// `try_run` returns `None` and `simulate` reports an `Overflow` event.
+pub trait SteeringModel {
+  fn up(&self, position: Position, distance: i32) -> Option<Position>;
+  fn down(&self, position: Position, distance: i32) -> Option<Position>;
+  fn forward(&self, position: Position, distance: i32) -> Option<Position>;
+  fn port(&self, position: Position, distance: i32) -> Option<Position>;
+  fn starboard(&self, position: Position, distance: i32) -> Option<Position>;
 
-  for command in commands {
+  fn apply(&self, position: Position, command: &Command) -> Option<Position> {
     match command {
-      Command::Up(direction) => position.2 -= direction,
-      Command::Down(direction) => position.2 += direction,
-      Command::Forward(direction) => {
-        position.0 += direction;
-        position.1 += position.2 * direction
-      }
+      Command::Up(distance) => self.up(position, *distance),
+      Command::Down(distance) => self.down(position, *distance),
+      Command::Forward(distance) => self.forward(position, *distance),
+      Command::Port(distance) => self.port(position, *distance),
+      Command::Starboard(distance) => self.starboard(position, *distance),
     }
   }
 
-  position.0 * position.1
+  fn try_run(&self, commands: &[Command]) -> Option<Position> {
+    commands
+      .iter()
+      .try_fold(Position::default(), |position, command| {
+        self.apply(position, command)
+      })
+  }
+
+  fn run(&self, commands: &[Command]) -> Position {
+    self.try_run(commands).expect("position overflowed")
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NaiveSteering;
+
+impl SteeringModel for NaiveSteering {
+  fn up(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      depth: position.depth.checked_sub(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn down(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      depth: position.depth.checked_add(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn forward(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      horizontal: position.horizontal.checked_add(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn port(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      lateral: position.lateral.checked_sub(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn starboard(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      lateral: position.lateral.checked_add(i64::from(distance))?,
+      ..position
+    })
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AimSteering;
+
+impl SteeringModel for AimSteering {
+  fn up(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      aim: position.aim.checked_sub(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn down(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      aim: position.aim.checked_add(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn forward(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      horizontal: position.horizontal.checked_add(i64::from(distance))?,
+      depth: position
+        .depth
+        .checked_add(position.aim.checked_mul(i64::from(distance))?)?,
+      lateral: position
+        .lateral
+        .checked_add(position.drift.checked_mul(i64::from(distance))?)?,
+      ..position
+    })
+  }
+
+  fn port(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      drift: position.drift.checked_sub(i64::from(distance))?,
+      ..position
+    })
+  }
+
+  fn starboard(&self, position: Position, distance: i32) -> Option<Position> {
+    Some(Position {
+      drift: position.drift.checked_add(i64::from(distance))?,
+      ..position
+    })
+  }
+}
+
+pub fn find_position(commands: &[Command]) -> Position {
+  NaiveSteering.run(commands)
+}
+
+pub fn find_position_with_aim(commands: &[Command]) -> Position {
+  AimSteering.run(commands)
 }
 
 #[cfg(test)]
@@ -78,7 +246,8 @@ mod tests {
         Command::Up(3),
         Command::Down(8),
         Command::Forward(2)
-      ]),
+      ])
+      .product(),
       150
     );
   }
@@ -93,8 +262,84 @@ mod tests {
         Command::Up(3),
         Command::Down(8),
         Command::Forward(2)
-      ]),
+      ])
+      .product(),
       900
     );
   }
+
+  #[test]
+  fn find_position_keeps_coordinates() {
+    let commands = [
+      Command::Forward(5),
+      Command::Down(5),
+      Command::Forward(8),
+      Command::Up(3),
+      Command::Down(8),
+      Command::Forward(2),
+    ];
+    assert_eq!(
+      find_position(&commands),
+      Position {
+        horizontal: 15,
+        depth: 10,
+        ..Position::default()
+      }
+    );
+    assert_eq!(
+      find_position_with_aim(&commands),
+      Position {
+        horizontal: 15,
+        depth: 60,
+        aim: 10,
+        ..Position::default()
+      }
+    );
+  }
+
+  #[test]
+  fn overflowing_positions() {
+    let commands = [Command::Down(i32::MAX), Command::Forward(i32::MAX)];
+    let position = AimSteering.run(&commands);
+    assert_eq!(position.product(), i128::from(i32::MAX).pow(3));
+    let commands = [
+      Command::Down(i32::MAX),
+      Command::Forward(i32::MAX),
+      Command::Forward(i32::MAX),
+      Command::Forward(i32::MAX),
+    ];
+    assert_eq!(AimSteering.try_run(&commands), None);
+    assert!(NaiveSteering.try_run(&commands).is_some());
+  }
+
+  #[test]
+  fn find_position_in_three_dimensions() {
+    let commands = [
+      Command::Forward(5),
+      Command::Starboard(3),
+      Command::Down(2),
+      Command::Forward(4),
+      Command::Port(5),
+      Command::Forward(1),
+    ];
+    assert_eq!(
+      find_position(&commands),
+      Position {
+        horizontal: 10,
+        depth: 2,
+        lateral: -2,
+        ..Position::default()
+      }
+    );
+    assert_eq!(
+      find_position_with_aim(&commands),
+      Position {
+        horizontal: 10,
+        depth: 10,
+        aim: 2,
+        lateral: 10,
+        drift: -2,
+      }
+    );
+  }
 }
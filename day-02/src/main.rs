@@ -1,9 +1,133 @@
-use std::env;
+use std::{env, fs, io, process};
+
+use day_02::SteeringModel;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
-    let commands = day_02::commands_from_file(filename);
-    println!("Part 1: {:?}", day_02::find_position(&commands));
-    println!("Part 2: {:?}", day_02::find_position_with_aim(&commands));
+    let commands = if args.iter().any(|arg| arg == "--script") {
+        day_02::script_from_file(filename).unwrap_or_else(|err| panic!("{}", err))
+    } else {
+        let file = fs::File::open(filename).expect("file doesn't exist");
+        day_02::parse_all_commands(io::BufReader::new(file)).unwrap_or_else(|errors| {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            process::exit(1);
+        })
+    };
+    let parts = [
+        ("Part 1", day_02::NaiveSteering.try_run(&commands)),
+        ("Part 2", day_02::AimSteering.try_run(&commands)),
+    ];
+    for (part, position) in parts {
+        match position {
+            Some(position) => println!("{}: {:?}", part, position.product()),
+            None => println!("{}: position overflowed", part),
+        }
+    }
+
+    // Recording keeps every position, so only do it when something is written.
+    let mut recorded = Vec::new();
+    if args.iter().any(|arg| arg == "--csv" || arg == "--svg") {
+        let attempts = [
+            (
+                "naive",
+                day_02::Trajectory::try_record(&day_02::NaiveSteering, &commands),
+            ),
+            (
+                "aim",
+                day_02::Trajectory::try_record(&day_02::AimSteering, &commands),
+            ),
+        ];
+        for (name, trajectory) in attempts {
+            match trajectory {
+                Some(trajectory) => recorded.push((name, trajectory)),
+                None => eprintln!("{}: position overflowed, trajectory skipped", name),
+            }
+        }
+    }
+    let trajectories: Vec<(&str, &day_02::Trajectory)> = recorded
+        .iter()
+        .map(|(name, trajectory)| (*name, trajectory))
+        .collect();
+    let mut options = args.iter().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--csv" => {
+                let file = fs::File::create(options.next().expect("missing csv filename"))
+                    .expect("couldn't create csv file");
+                day_02::write_trajectories_csv(io::BufWriter::new(file), &trajectories)
+                    .expect("couldn't write csv");
+            }
+            "--svg" => fs::write(
+                options.next().expect("missing svg filename"),
+                day_02::trajectories_svg(&trajectories, 1200, 800),
+            )
+            .expect("couldn't write svg"),
+            "--seabed" => {
+                let limits = day_02::SafetyLimits {
+                    surface: 0,
+                    seabed: Some(
+                        options
+                            .next()
+                            .expect("missing seabed depth")
+                            .parse()
+                            .expect("not a number"),
+                    ),
+                };
+                let simulations = [
+                    (
+                        "naive",
+                        day_02::simulate(&day_02::NaiveSteering, &commands, limits),
+                    ),
+                    (
+                        "aim",
+                        day_02::simulate(&day_02::AimSteering, &commands, limits),
+                    ),
+                ];
+                for (name, simulation) in simulations {
+                    for event in simulation.events {
+                        println!("{}: {:?}", name, event);
+                    }
+                }
+            }
+            "--fleet" => {
+                let within: i64 = options
+                    .next()
+                    .expect("missing fleet distance")
+                    .parse()
+                    .expect("not a number");
+                let mut submarines = vec![day_02::Submarine {
+                    name: filename.clone(),
+                    commands: commands.clone(),
+                }];
+                submarines.extend(options.by_ref().map(|name| day_02::Submarine {
+                    name: name.clone(),
+                    commands: day_02::commands_from_file(name),
+                }));
+                let runs = [
+                    (
+                        "naive",
+                        day_02::simulate_fleet(&day_02::NaiveSteering, &submarines),
+                    ),
+                    (
+                        "aim",
+                        day_02::simulate_fleet(&day_02::AimSteering, &submarines),
+                    ),
+                ];
+                for (model, run) in runs {
+                    println!("{}:\n{}", model, run);
+                    for approach in run.close_approaches(within) {
+                        println!(
+                            "{} and {} within {:.1} at step {}",
+                            approach.first, approach.second, approach.distance, approach.step
+                        );
+                    }
+                }
+            }
+            "--script" => {}
+            _ => panic!("unknown option {}", option),
+        }
+    }
 }
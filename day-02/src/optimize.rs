@@ -0,0 +1,205 @@
+use crate::{AimSteering, Command, NaiveSteering};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimized {
+  pub commands: Vec<Command>,
+  pub removed: usize,
+}
+
+impl Optimized {
+  fn new(original: &[Command], commands: Vec<Command>) -> Optimized {
+    Optimized {
+      removed: original.len().saturating_sub(commands.len()),
+      commands,
+    }
+  }
+}
+
+// An optimized program ends in the same position (including aim) as the
+// original under that steering model, though it may take a different path.
+pub trait Optimizer {
+  fn optimize(&self, commands: &[Command]) -> Optimized;
+}
+
+fn push_distance(commands: &mut Vec<Command>, distance: i64, command: fn(i32) -> Command) {
+  let mut remaining = distance;
+  while remaining != 0 {
+    let chunk = remaining.clamp(i64::from(i32::MIN), i64::from(i32::MAX));
+    commands.push(command(chunk as i32));
+    remaining -= chunk;
+  }
+}
+
+fn push_vertical(commands: &mut Vec<Command>, distance: i64) {
+  if distance < 0 {
+    push_distance(commands, -distance, Command::Up);
+  } else {
+    push_distance(commands, distance, Command::Down);
+  }
+}
+
+fn push_lateral(commands: &mut Vec<Command>, distance: i64) {
+  if distance < 0 {
+    push_distance(commands, -distance, Command::Port);
+  } else {
+    push_distance(commands, distance, Command::Starboard);
+  }
+}
+
+// Every command only moves one coordinate, so the order doesn't matter and all
+// moves of the same kind collapse into one.
+impl Optimizer for NaiveSteering {
+  fn optimize(&self, commands: &[Command]) -> Optimized {
+    let (mut forward, mut vertical, mut lateral) = (0, 0, 0);
+    for command in commands {
+      match command {
+        Command::Forward(distance) => forward += i64::from(*distance),
+        Command::Down(distance) => vertical += i64::from(*distance),
+        Command::Up(distance) => vertical -= i64::from(*distance),
+        Command::Starboard(distance) => lateral += i64::from(*distance),
+        Command::Port(distance) => lateral -= i64::from(*distance),
+      }
+    }
+
+    let mut optimized = Vec::new();
+    push_distance(&mut optimized, forward, Command::Forward);
+    push_vertical(&mut optimized, vertical);
+    push_lateral(&mut optimized, lateral);
+    Optimized::new(commands, optimized)
+  }
+}
+
+// Aim and drift changes only matter at the next `Forward`, so the vertical and
+// lateral moves between two `Forward`s merge into one each, and `Forward`s with
+// no change between them merge as well.
+impl Optimizer for AimSteering {
+  fn optimize(&self, commands: &[Command]) -> Optimized {
+    let mut optimized = Vec::new();
+    let (mut forward, mut vertical, mut lateral) = (0, 0, 0);
+
+    for command in commands {
+      match command {
+        Command::Down(distance) => vertical += i64::from(*distance),
+        Command::Up(distance) => vertical -= i64::from(*distance),
+        Command::Starboard(distance) => lateral += i64::from(*distance),
+        Command::Port(distance) => lateral -= i64::from(*distance),
+        Command::Forward(distance) => {
+          if vertical != 0 || lateral != 0 {
+            push_distance(&mut optimized, forward, Command::Forward);
+            push_vertical(&mut optimized, vertical);
+            push_lateral(&mut optimized, lateral);
+            forward = 0;
+            vertical = 0;
+            lateral = 0;
+          }
+          forward += i64::from(*distance);
+        }
+      }
+    }
+    push_distance(&mut optimized, forward, Command::Forward);
+    push_vertical(&mut optimized, vertical);
+    push_lateral(&mut optimized, lateral);
+
+    Optimized::new(commands, optimized)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::SteeringModel;
+
+  const PROGRAM: [Command; 9] = [
+    Command::Down(3),
+    Command::Up(1),
+    Command::Down(2),
+    Command::Forward(5),
+    Command::Forward(2),
+    Command::Up(4),
+    Command::Down(4),
+    Command::Forward(1),
+    Command::Up(2),
+  ];
+
+  #[test]
+  fn optimize_naive() {
+    let optimized = NaiveSteering.optimize(&PROGRAM);
+    assert_eq!(
+      optimized.commands,
+      vec![Command::Forward(8), Command::Down(2)]
+    );
+    assert_eq!(optimized.removed, 7);
+    assert_eq!(
+      NaiveSteering.run(&optimized.commands),
+      NaiveSteering.run(&PROGRAM)
+    );
+  }
+
+  #[test]
+  fn optimize_aim() {
+    let optimized = AimSteering.optimize(&PROGRAM);
+    assert_eq!(
+      optimized.commands,
+      vec![Command::Down(4), Command::Forward(8), Command::Up(2)]
+    );
+    assert_eq!(optimized.removed, 6);
+    assert_eq!(
+      AimSteering.run(&optimized.commands),
+      AimSteering.run(&PROGRAM)
+    );
+  }
+
+  #[test]
+  fn optimize_aim_keeps_aim_changes_between_forwards() {
+    let program = [
+      Command::Forward(1),
+      Command::Down(1),
+      Command::Forward(1),
+      Command::Down(1),
+      Command::Up(1),
+      Command::Forward(1),
+    ];
+    let optimized = AimSteering.optimize(&program);
+    assert_eq!(
+      optimized.commands,
+      vec![Command::Forward(1), Command::Down(1), Command::Forward(2)]
+    );
+    assert_eq!(
+      AimSteering.run(&optimized.commands),
+      AimSteering.run(&program)
+    );
+  }
+
+  #[test]
+  fn optimize_splits_merged_distances_that_overflow() {
+    let program = [Command::Forward(i32::MAX), Command::Forward(i32::MAX)];
+    assert_eq!(NaiveSteering.optimize(&program).commands, program);
+  }
+
+  #[test]
+  fn optimize_lateral_moves() {
+    let program = [
+      Command::Port(2),
+      Command::Starboard(5),
+      Command::Forward(3),
+      Command::Down(1),
+      Command::Port(1),
+      Command::Port(2),
+      Command::Forward(1),
+    ];
+    assert_eq!(
+      AimSteering.optimize(&program).commands,
+      vec![
+        Command::Starboard(3),
+        Command::Forward(3),
+        Command::Down(1),
+        Command::Port(3),
+        Command::Forward(1)
+      ]
+    );
+    assert_eq!(
+      NaiveSteering.optimize(&program).commands,
+      vec![Command::Forward(4), Command::Down(1)]
+    );
+  }
+}
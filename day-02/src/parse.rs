@@ -0,0 +1,159 @@
+use std::{error::Error, fmt, io::BufRead};
+
+use crate::{Command, Direction};
+
+pub const DIRECTIONS: [&str; Direction::ALL.len()] = {
+  let mut names = [""; Direction::ALL.len()];
+  let mut idx = 0;
+  while idx < names.len() {
+    names[idx] = Direction::ALL[idx].name();
+    idx += 1;
+  }
+  names
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+  MissingDirection,
+  UnknownDirection(String),
+  MissingDistance,
+  InvalidDistance(String),
+  UnexpectedInput(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+  pub line: usize,
+  pub text: String,
+  pub kind: ParseErrorKind,
+  pub expected: &'static [&'static str],
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "line {}: ", self.line)?;
+    match &self.kind {
+      ParseErrorKind::MissingDirection => write!(f, "missing direction")?,
+      ParseErrorKind::UnknownDirection(direction) => {
+        write!(f, "unknown direction {:?}", direction)?
+      }
+      ParseErrorKind::MissingDistance => write!(f, "missing distance")?,
+      ParseErrorKind::InvalidDistance(distance) => {
+        write!(f, "couldn't parse distance {:?}", distance)?
+      }
+      ParseErrorKind::UnexpectedInput(input) => write!(f, "unexpected {:?}", input)?,
+    }
+    write!(
+      f,
+      " in {:?} (expected one of {} followed by a distance)",
+      self.text,
+      self.expected.join(", ")
+    )
+  }
+}
+
+impl Error for ParseError {}
+
+pub fn parse_command(text: &str) -> Result<Command, ParseErrorKind> {
+  let mut parts = text.split_whitespace();
+  let direction: Direction = parts
+    .next()
+    .ok_or(ParseErrorKind::MissingDirection)?
+    .parse()?;
+  let distance = parts.next().ok_or(ParseErrorKind::MissingDistance)?;
+  let distance = distance
+    .parse::<i32>()
+    .map_err(|_| ParseErrorKind::InvalidDistance(distance.to_string()))?;
+  if let Some(extra) = parts.next() {
+    return Err(ParseErrorKind::UnexpectedInput(extra.to_string()));
+  }
+
+  Ok(direction.command(distance))
+}
+
+fn parse_lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Command, ParseError>> {
+  reader.lines().enumerate().map(|(idx, line)| {
+    let text = line.expect("couldn't parse line");
+    parse_command(&text).map_err(|kind| ParseError {
+      line: idx + 1,
+      text,
+      kind,
+      expected: &DIRECTIONS,
+    })
+  })
+}
+
+pub fn parse_commands<R: BufRead>(reader: R) -> Result<Vec<Command>, ParseError> {
+  parse_lines(reader).collect()
+}
+
+pub fn parse_all_commands<R: BufRead>(reader: R) -> Result<Vec<Command>, Vec<ParseError>> {
+  let mut commands = Vec::new();
+  let mut errors = Vec::new();
+  for result in parse_lines(reader) {
+    match result {
+      Ok(command) => commands.push(command),
+      Err(error) => errors.push(error),
+    }
+  }
+
+  if errors.is_empty() {
+    Ok(commands)
+  } else {
+    Err(errors)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BROKEN: &str = "forward 5\nsideways 2\ndown\nup three\nforward 1 2\n\ndown 4\n";
+
+  #[test]
+  fn parse_valid_commands() {
+    assert_eq!(
+      parse_commands("forward 5\ndown 5\nup 3\nport 2\nstarboard 1\n".as_bytes()),
+      Ok(vec![
+        Command::Forward(5),
+        Command::Down(5),
+        Command::Up(3),
+        Command::Port(2),
+        Command::Starboard(1)
+      ])
+    );
+  }
+
+  #[test]
+  fn parse_stops_at_first_error() {
+    let error = parse_commands(BROKEN.as_bytes()).unwrap_err();
+    assert_eq!(error.line, 2);
+    assert_eq!(error.text, "sideways 2");
+    assert_eq!(
+      error.kind,
+      ParseErrorKind::UnknownDirection("sideways".to_string())
+    );
+    assert_eq!(
+      error.to_string(),
+      "line 2: unknown direction \"sideways\" in \"sideways 2\" (expected one of up, down, forward, port, starboard followed by a distance)"
+    );
+  }
+
+  #[test]
+  fn parse_collects_all_errors() {
+    let errors = parse_all_commands(BROKEN.as_bytes()).unwrap_err();
+    assert_eq!(
+      errors
+        .into_iter()
+        .map(|error| (error.line, error.kind))
+        .collect::<Vec<_>>(),
+      vec![
+        (2, ParseErrorKind::UnknownDirection("sideways".to_string())),
+        (3, ParseErrorKind::MissingDistance),
+        (4, ParseErrorKind::InvalidDistance("three".to_string())),
+        (5, ParseErrorKind::UnexpectedInput("2".to_string())),
+        (6, ParseErrorKind::MissingDirection),
+      ]
+    );
+  }
+}
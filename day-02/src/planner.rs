@@ -0,0 +1,328 @@
+use std::{error::Error, fmt};
+
+use crate::{AimSteering, Command, NaiveSteering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+  pub horizontal: i64,
+  pub depth: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteError {
+  InvalidStep(i32),
+  Unreachable(Target),
+}
+
+impl fmt::Display for RouteError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      RouteError::InvalidStep(step) => write!(f, "maximum step {} must be positive", step),
+      RouteError::Unreachable(target) => write!(
+        f,
+        "horizontal {} and depth {} can't be reached",
+        target.horizontal, target.depth
+      ),
+    }
+  }
+}
+
+impl Error for RouteError {}
+
+// `plan_route` is minimal wherever an implementation says so. Step-limited
+// routes from `plan_heuristic_route` are only short: finding the shortest one
+// for the aim model is an integer programming problem.
+pub trait RoutePlanner {
+  fn plan_route(&self, target: Target) -> Result<Vec<Command>, RouteError> {
+    self.plan_heuristic_route(target, None)
+  }
+
+  fn plan_heuristic_route(
+    &self,
+    target: Target,
+    max_step: Option<i32>,
+  ) -> Result<Vec<Command>, RouteError>;
+}
+
+fn step_size(max_step: Option<i32>) -> Result<i64, RouteError> {
+  match max_step {
+    Some(step) if step <= 0 => Err(RouteError::InvalidStep(step)),
+    Some(step) => Ok(i64::from(step)),
+    None => Ok(i64::from(i32::MAX)),
+  }
+}
+
+fn moves(distance: i64, step: i64) -> u128 {
+  u128::from(distance.unsigned_abs().div_ceil(step as u64))
+}
+
+fn push_moves(commands: &mut Vec<Command>, distance: i64, step: i64, command: fn(i32) -> Command) {
+  let mut remaining = distance;
+  while remaining > 0 {
+    let chunk = remaining.min(step);
+    commands.push(command(chunk as i32));
+    remaining -= chunk;
+  }
+}
+
+fn push_vertical(commands: &mut Vec<Command>, distance: i64, step: i64) {
+  if distance < 0 {
+    push_moves(commands, -distance, step, Command::Up);
+  } else {
+    push_moves(commands, distance, step, Command::Down);
+  }
+}
+
+// Trial division stops after `DIVISOR_SEARCH` candidates so huge depths stay
+// fast. The slots that make a route minimal are checked directly instead.
+const DIVISOR_SEARCH: u64 = 1 << 20;
+
+fn divisors(n: i64) -> Vec<i64> {
+  let n = n.unsigned_abs();
+  let mut divisors = Vec::new();
+  let mut i = 1;
+  while i <= DIVISOR_SEARCH && i <= n / i {
+    if n.is_multiple_of(i) {
+      divisors.push(i);
+      divisors.push(n / i);
+    }
+    i += 1;
+  }
+
+  divisors
+    .into_iter()
+    .filter_map(|divisor| i64::try_from(divisor).ok())
+    .collect()
+}
+
+// Moving straight there is always minimal for the naive model, with or
+// without a step limit.
+impl RoutePlanner for NaiveSteering {
+  fn plan_heuristic_route(
+    &self,
+    target: Target,
+    max_step: Option<i32>,
+  ) -> Result<Vec<Command>, RouteError> {
+    let step = step_size(max_step)?;
+    if target.horizontal < 0 {
+      return Err(RouteError::Unreachable(target));
+    }
+
+    let mut commands = Vec::new();
+    push_moves(&mut commands, target.horizontal, step, Command::Forward);
+    push_vertical(&mut commands, target.depth, step);
+    Ok(commands)
+  }
+}
+
+// A candidate aim plan: set the aim to `initial`, move forward until `slot`
+// horizontal distance is left, change the aim by `change`, then move the rest
+// of the way.
+struct AimPlan {
+  initial: i64,
+  slot: i64,
+  change: i64,
+}
+
+impl AimPlan {
+  fn len(&self, horizontal: i64, step: i64) -> u128 {
+    moves(self.initial, step)
+      + moves(horizontal - self.slot, step)
+      + moves(self.change, step)
+      + moves(self.slot, step)
+  }
+
+  fn commands(&self, horizontal: i64, step: i64) -> Vec<Command> {
+    let mut commands = Vec::new();
+    push_vertical(&mut commands, self.initial, step);
+    push_moves(
+      &mut commands,
+      horizontal - self.slot,
+      step,
+      Command::Forward,
+    );
+    push_vertical(&mut commands, self.change, step);
+    push_moves(&mut commands, self.slot, step, Command::Forward);
+    commands
+  }
+}
+
+// Picks the shortest of the plans that change aim at most twice. Without a
+// step limit, and with the horizontal distance and depth each fitting in one
+// command, that's minimal: any target is reachable in three commands
+// (`forward h - 1`, `down d`, `forward 1`) and two are only enough when `h`
+// divides `d` (`down d / h`, `forward h`). Both of those slots are always
+// candidates. With a limit it's a heuristic.
+impl RoutePlanner for AimSteering {
+  fn plan_heuristic_route(
+    &self,
+    target: Target,
+    max_step: Option<i32>,
+  ) -> Result<Vec<Command>, RouteError> {
+    let step = step_size(max_step)?;
+    let Target { horizontal, depth } = target;
+    if horizontal < 0 || (horizontal == 0 && depth != 0) {
+      return Err(RouteError::Unreachable(target));
+    }
+
+    if depth == 0 {
+      let mut commands = Vec::new();
+      push_moves(&mut commands, horizontal, step, Command::Forward);
+      return Ok(commands);
+    }
+
+    let mut slots = divisors(depth);
+    slots.extend([1, horizontal]);
+    let mut candidates: Vec<AimPlan> = slots
+      .into_iter()
+      .filter(|&slot| slot <= horizontal && depth % slot == 0)
+      .map(|slot| AimPlan {
+        initial: 0,
+        slot,
+        change: depth / slot,
+      })
+      .collect();
+
+    let initial = depth.div_euclid(horizontal);
+    let initials = [
+      initial.checked_sub(1),
+      Some(initial),
+      initial.checked_add(1),
+    ];
+    for initial in initials.into_iter().flatten() {
+      let remainder = match initial
+        .checked_mul(horizontal)
+        .and_then(|travelled| depth.checked_sub(travelled))
+      {
+        Some(remainder) => remainder,
+        None => continue,
+      };
+      let mut slots = divisors(remainder);
+      slots.push(1);
+      candidates.extend(
+        slots
+          .into_iter()
+          .filter(|&slot| slot < horizontal && remainder % slot == 0)
+          .map(|slot| AimPlan {
+            initial,
+            slot,
+            change: remainder / slot,
+          }),
+      );
+    }
+
+    let plan = candidates
+      .into_iter()
+      .min_by_key(|plan| plan.len(horizontal, step))
+      .expect("a slot of 1 always divides the depth");
+    Ok(plan.commands(horizontal, step))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::SteeringModel;
+
+  fn reaches<M: SteeringModel>(model: &M, commands: &[Command], target: Target) {
+    let position = model.run(commands);
+    assert_eq!(
+      (position.horizontal, position.depth),
+      (target.horizontal, target.depth)
+    );
+  }
+
+  #[test]
+  fn naive_route() {
+    let target = Target {
+      horizontal: 15,
+      depth: -7,
+    };
+    let commands = NaiveSteering.plan_heuristic_route(target, Some(4)).unwrap();
+    assert_eq!(commands.len(), 6);
+    reaches(&NaiveSteering, &commands, target);
+  }
+
+  #[test]
+  fn aim_route_without_limit() {
+    let exact = Target {
+      horizontal: 15,
+      depth: 60,
+    };
+    assert_eq!(
+      AimSteering.plan_route(exact),
+      Ok(vec![Command::Down(4), Command::Forward(15)])
+    );
+
+    let target = Target {
+      horizontal: 1992,
+      depth: 1_064_089,
+    };
+    let commands = AimSteering.plan_route(target).unwrap();
+    assert_eq!(commands.len(), 3);
+    reaches(&AimSteering, &commands, target);
+
+    let level = Target {
+      horizontal: 10,
+      depth: 0,
+    };
+    assert_eq!(
+      AimSteering.plan_route(level),
+      Ok(vec![Command::Forward(10)])
+    );
+    let start = Target {
+      horizontal: 0,
+      depth: 0,
+    };
+    assert_eq!(AimSteering.plan_route(start), Ok(vec![]));
+
+    let beyond_divisor_search = Target {
+      horizontal: 1_048_583,
+      depth: 1_048_583 * 1_048_589,
+    };
+    assert_eq!(
+      AimSteering.plan_route(beyond_divisor_search),
+      Ok(vec![Command::Down(1_048_589), Command::Forward(1_048_583)])
+    );
+  }
+
+  #[test]
+  fn aim_route_with_limit() {
+    for (horizontal, depth) in [(15, 61), (40, -333), (7, 1), (100, 12345)] {
+      let target = Target { horizontal, depth };
+      let commands = AimSteering.plan_heuristic_route(target, Some(9)).unwrap();
+      assert!(commands.iter().all(|command| match command {
+        Command::Up(n)
+        | Command::Down(n)
+        | Command::Forward(n)
+        | Command::Port(n)
+        | Command::Starboard(n) => *n <= 9,
+      }));
+      reaches(&AimSteering, &commands, target);
+    }
+  }
+
+  #[test]
+  fn unreachable_routes() {
+    let target = Target {
+      horizontal: 0,
+      depth: 5,
+    };
+    assert_eq!(
+      AimSteering.plan_heuristic_route(target, None),
+      Err(RouteError::Unreachable(target))
+    );
+    assert_eq!(
+      NaiveSteering.plan_heuristic_route(target, Some(0)),
+      Err(RouteError::InvalidStep(0))
+    );
+  }
+
+  #[test]
+  fn extreme_distances_dont_overflow() {
+    assert_eq!(moves(i64::MIN, 1), 1 << 63);
+    assert_eq!(divisors(i64::MIN).len(), 41);
+    let divisors = divisors(i64::MAX);
+    assert!(divisors.contains(&1) && divisors.contains(&i64::MAX));
+  }
+}
@@ -0,0 +1,129 @@
+use crate::{Command, Position, SteeringModel};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SafetyLimits {
+  pub surface: i64,
+  pub seabed: Option<i64>,
+}
+
+// `command` is the index of the command that caused the event, and `depth` is
+// where that command would have taken the submarine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafetyEvent {
+  SurfaceBreach { command: usize, depth: i64 },
+  SeabedCollision { command: usize, depth: i64 },
+  Overflow { command: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+  pub position: Position,
+  pub events: Vec<SafetyEvent>,
+}
+
+impl Simulation {
+  pub fn is_safe(&self) -> bool {
+    self.events.is_empty()
+  }
+}
+
+// Breaching a limit keeps the submarine at that limit and carries on; an
+// overflow stops the simulation at the last position that could be computed.
+pub fn simulate<M: SteeringModel + ?Sized>(
+  model: &M,
+  commands: &[Command],
+  limits: SafetyLimits,
+) -> Simulation {
+  let mut position = Position::default();
+  let mut events = Vec::new();
+
+  for (idx, command) in commands.iter().enumerate() {
+    let next = match model.apply(position, command) {
+      Some(next) => next,
+      None => {
+        events.push(SafetyEvent::Overflow { command: idx });
+        break;
+      }
+    };
+
+    position = next;
+    if next.depth < limits.surface {
+      events.push(SafetyEvent::SurfaceBreach {
+        command: idx,
+        depth: next.depth,
+      });
+      position.depth = limits.surface;
+    }
+    if let Some(seabed) = limits.seabed.filter(|&seabed| next.depth > seabed) {
+      events.push(SafetyEvent::SeabedCollision {
+        command: idx,
+        depth: next.depth,
+      });
+      position.depth = seabed;
+    }
+  }
+
+  Simulation { position, events }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{AimSteering, NaiveSteering};
+
+  #[test]
+  fn safe_run_matches_model() {
+    let commands = [
+      Command::Forward(5),
+      Command::Down(5),
+      Command::Forward(8),
+      Command::Up(3),
+      Command::Down(8),
+      Command::Forward(2),
+    ];
+    let simulation = simulate(&AimSteering, &commands, SafetyLimits::default());
+    assert!(simulation.is_safe());
+    assert_eq!(simulation.position, AimSteering.run(&commands));
+  }
+
+  #[test]
+  fn surface_and_seabed_events() {
+    let commands = [
+      Command::Up(2),
+      Command::Down(4),
+      Command::Down(10),
+      Command::Up(1),
+    ];
+    let limits = SafetyLimits {
+      surface: 0,
+      seabed: Some(8),
+    };
+    let simulation = simulate(&NaiveSteering, &commands, limits);
+    assert_eq!(
+      simulation.events,
+      vec![
+        SafetyEvent::SurfaceBreach {
+          command: 0,
+          depth: -2
+        },
+        SafetyEvent::SeabedCollision {
+          command: 2,
+          depth: 14
+        },
+      ]
+    );
+    assert_eq!(simulation.position.depth, 7);
+  }
+
+  #[test]
+  fn overflow_stops_simulation() {
+    let mut commands = vec![Command::Down(i32::MAX)];
+    commands.extend(vec![Command::Forward(i32::MAX); 3]);
+    let simulation = simulate(&AimSteering, &commands, SafetyLimits::default());
+    assert_eq!(
+      simulation.events,
+      vec![SafetyEvent::Overflow { command: 3 }]
+    );
+    assert_eq!(simulation.position.horizontal, 2 * i64::from(i32::MAX));
+  }
+}
@@ -0,0 +1,378 @@
+use std::{collections::HashMap, error::Error, fmt, fs, path::Path};
+
+use crate::{Command, Direction};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptErrorKind {
+  UnknownStatement(String),
+  UnknownConstant(String),
+  InvalidNumber(String),
+  MissingArgument(String),
+  DuplicateName(String),
+  ReservedName(String),
+  TooManyCommands,
+  UnexpectedInput(String),
+  MissingBrace(String),
+  NestedMacro,
+  UnclosedBlock,
+  UnexpectedClose,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+  pub line: usize,
+  pub kind: ScriptErrorKind,
+}
+
+impl fmt::Display for ScriptError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "line {}: ", self.line)?;
+    match &self.kind {
+      ScriptErrorKind::UnknownStatement(word) => write!(f, "unknown statement {:?}", word),
+      ScriptErrorKind::UnknownConstant(name) => write!(f, "unknown constant {:?}", name),
+      ScriptErrorKind::InvalidNumber(text) => write!(f, "{:?} is not a number", text),
+      ScriptErrorKind::MissingArgument(word) => write!(f, "{:?} is missing an argument", word),
+      ScriptErrorKind::DuplicateName(name) => write!(f, "{:?} is already defined", name),
+      ScriptErrorKind::ReservedName(name) => write!(f, "{:?} can't be used as a name", name),
+      ScriptErrorKind::TooManyCommands => {
+        write!(f, "script expands to more than {} commands", MAX_COMMANDS)
+      }
+      ScriptErrorKind::UnexpectedInput(word) => write!(f, "unexpected {:?}", word),
+      ScriptErrorKind::MissingBrace(word) => write!(f, "{:?} must end with \"{{\"", word),
+      ScriptErrorKind::NestedMacro => write!(f, "macros can only be defined at the top level"),
+      ScriptErrorKind::UnclosedBlock => write!(f, "block is never closed"),
+      ScriptErrorKind::UnexpectedClose => write!(f, "unexpected \"}}\""),
+    }
+  }
+}
+
+impl Error for ScriptError {}
+
+const MAX_COMMANDS: usize = 1 << 24;
+const KEYWORDS: [&str; 4] = ["const", "repeat", "macro", "}"];
+
+#[derive(Default)]
+struct Compiler {
+  constants: HashMap<String, i32>,
+  macros: HashMap<String, Vec<Command>>,
+}
+
+impl Compiler {
+  fn argument(&self, line: usize, keyword: &str, arg: Option<&str>) -> Result<i32, ScriptError> {
+    let error = |kind| ScriptError { line, kind };
+    let arg = arg.ok_or_else(|| error(ScriptErrorKind::MissingArgument(keyword.to_string())))?;
+
+    if arg.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+      arg
+        .parse::<i32>()
+        .map_err(|_| error(ScriptErrorKind::InvalidNumber(arg.to_string())))
+    } else {
+      self
+        .constants
+        .get(arg)
+        .copied()
+        .ok_or_else(|| error(ScriptErrorKind::UnknownConstant(arg.to_string())))
+    }
+  }
+
+  // Names that would be read as a statement or a number could never be used.
+  fn define(&self, line: usize, name: &str) -> Result<(), ScriptError> {
+    if KEYWORDS.contains(&name)
+      || name.parse::<Direction>().is_ok()
+      || name.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+    {
+      return Err(ScriptError {
+        line,
+        kind: ScriptErrorKind::ReservedName(name.to_string()),
+      });
+    }
+    if self.constants.contains_key(name) || self.macros.contains_key(name) {
+      return Err(ScriptError {
+        line,
+        kind: ScriptErrorKind::DuplicateName(name.to_string()),
+      });
+    }
+
+    Ok(())
+  }
+
+  // Appends `body` `times` times, failing before anything is added if that
+  // would take the block past `MAX_COMMANDS`. Every block is checked, so the
+  // whole script is too.
+  fn expand(
+    line: usize,
+    commands: &mut Vec<Command>,
+    body: &[Command],
+    times: usize,
+  ) -> Result<(), ScriptError> {
+    let total = body
+      .len()
+      .checked_mul(times)
+      .and_then(|added| added.checked_add(commands.len()))
+      .filter(|&total| total <= MAX_COMMANDS)
+      .ok_or(ScriptError {
+        line,
+        kind: ScriptErrorKind::TooManyCommands,
+      })?;
+    commands.reserve(total - commands.len());
+    if !body.is_empty() {
+      for _ in 0..times {
+        commands.extend_from_slice(body);
+      }
+    }
+
+    Ok(())
+  }
+
+  // Checks that a statement ends with `{` exactly when it opens a block and
+  // that nothing else follows its arguments.
+  fn finish<'a>(
+    line: usize,
+    keyword: &str,
+    mut words: impl Iterator<Item = &'a str>,
+    opens_block: bool,
+  ) -> Result<(), ScriptError> {
+    let error = |kind| ScriptError { line, kind };
+    if opens_block {
+      match words.next() {
+        Some("{") => {}
+        Some(word) => return Err(error(ScriptErrorKind::UnexpectedInput(word.to_string()))),
+        None => return Err(error(ScriptErrorKind::MissingBrace(keyword.to_string()))),
+      }
+    }
+    match words.next() {
+      Some(word) => Err(error(ScriptErrorKind::UnexpectedInput(word.to_string()))),
+      None => Ok(()),
+    }
+  }
+
+  // Compiles statements until the `}` closing the current block, or the end
+  // of the script for the top level.
+  fn block<'a>(
+    &mut self,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    top_level: bool,
+    opened_at: usize,
+  ) -> Result<Vec<Command>, ScriptError> {
+    let mut commands = Vec::new();
+
+    while let Some((line, text)) = lines.next() {
+      let error = |kind| ScriptError { line, kind };
+      let mut words = text.split_whitespace();
+      let keyword = match words.next() {
+        Some(keyword) => keyword,
+        None => continue,
+      };
+
+      match keyword {
+        "}" => {
+          Compiler::finish(line, keyword, words, false)?;
+          if top_level {
+            return Err(error(ScriptErrorKind::UnexpectedClose));
+          }
+          return Ok(commands);
+        }
+        "const" => {
+          let name = words
+            .next()
+            .ok_or_else(|| error(ScriptErrorKind::MissingArgument(keyword.to_string())))?;
+          self.define(line, name)?;
+          let value = match words.next() {
+            Some("=") => self.argument(line, name, words.next())?,
+            other => self.argument(line, name, other)?,
+          };
+          Compiler::finish(line, keyword, words, false)?;
+          self.constants.insert(name.to_string(), value);
+        }
+        "repeat" => {
+          let count = self.argument(line, keyword, words.next())?;
+          let count = usize::try_from(count)
+            .map_err(|_| error(ScriptErrorKind::InvalidNumber(count.to_string())))?;
+          Compiler::finish(line, keyword, words, true)?;
+          let body = self.block(lines, false, line)?;
+          Compiler::expand(line, &mut commands, &body, count)?;
+        }
+        "macro" => {
+          if !top_level {
+            return Err(error(ScriptErrorKind::NestedMacro));
+          }
+          let name = words
+            .next()
+            .ok_or_else(|| error(ScriptErrorKind::MissingArgument(keyword.to_string())))?;
+          self.define(line, name)?;
+          Compiler::finish(line, keyword, words, true)?;
+          let body = self.block(lines, false, line)?;
+          self.macros.insert(name.to_string(), body);
+        }
+        name => {
+          if let Ok(direction) = name.parse::<Direction>() {
+            let distance = self.argument(line, keyword, words.next())?;
+            Compiler::finish(line, keyword, words, false)?;
+            Compiler::expand(line, &mut commands, &[direction.command(distance)], 1)?;
+            continue;
+          }
+
+          let body = self
+            .macros
+            .get(name)
+            .ok_or_else(|| error(ScriptErrorKind::UnknownStatement(name.to_string())))?;
+          Compiler::finish(line, keyword, words, false)?;
+          Compiler::expand(line, &mut commands, body, 1)?;
+        }
+      }
+    }
+
+    if !top_level {
+      return Err(ScriptError {
+        line: opened_at,
+        kind: ScriptErrorKind::UnclosedBlock,
+      });
+    }
+
+    Ok(commands)
+  }
+}
+
+// Scripts are line based: one statement per line, `#` starts a comment, and
+// `repeat N {` / `macro name {` open blocks that end at a line with just `}`.
+// Constants and macros have to be defined before they're used.
+pub fn compile_script(source: &str) -> Result<Vec<Command>, ScriptError> {
+  let mut lines = source
+    .lines()
+    .enumerate()
+    .map(|(idx, line)| (idx + 1, line.split('#').next().unwrap_or_default()));
+
+  Compiler::default().block(&mut lines, true, 0)
+}
+
+pub fn script_from_file(filename: impl AsRef<Path>) -> Result<Vec<Command>, ScriptError> {
+  compile_script(&fs::read_to_string(filename).expect("file doesn't exist"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn compile_plain_commands() {
+    assert_eq!(
+      compile_script("forward 5\n\n# dive\ndown 5 # a bit\nup 3\n"),
+      Ok(vec![Command::Forward(5), Command::Down(5), Command::Up(3)])
+    );
+  }
+
+  #[test]
+  fn compile_constants_repeats_and_macros() {
+    let script = "
+const STEP = 2
+macro dive {
+  down STEP
+  forward 1
+}
+repeat 2 {
+  dive
+  repeat 2 {
+    forward STEP
+  }
+}
+up 4
+";
+    assert_eq!(
+      compile_script(script),
+      Ok(vec![
+        Command::Down(2),
+        Command::Forward(1),
+        Command::Forward(2),
+        Command::Forward(2),
+        Command::Down(2),
+        Command::Forward(1),
+        Command::Forward(2),
+        Command::Forward(2),
+        Command::Up(4),
+      ])
+    );
+  }
+
+  #[test]
+  fn compile_errors_have_line_numbers() {
+    let error = |line, kind| Err(ScriptError { line, kind });
+    assert_eq!(
+      compile_script("forward 1\nsideways 2\n"),
+      error(2, ScriptErrorKind::UnknownStatement("sideways".to_string()))
+    );
+    assert_eq!(
+      compile_script("down DEEP\n"),
+      error(1, ScriptErrorKind::UnknownConstant("DEEP".to_string()))
+    );
+    assert_eq!(
+      compile_script("forward\n"),
+      error(1, ScriptErrorKind::MissingArgument("forward".to_string()))
+    );
+    assert_eq!(
+      compile_script("up 1\nrepeat 3 {\n  up 1\n"),
+      error(2, ScriptErrorKind::UnclosedBlock)
+    );
+    assert_eq!(
+      compile_script("}\n"),
+      error(1, ScriptErrorKind::UnexpectedClose)
+    );
+    assert_eq!(
+      compile_script("repeat 2 {\nmacro m {\n}\n}\n"),
+      error(2, ScriptErrorKind::NestedMacro)
+    );
+    assert_eq!(
+      compile_script("repeat 2 { forward 1 }\nforward 3\n}\n"),
+      error(1, ScriptErrorKind::UnexpectedInput("forward".to_string()))
+    );
+    assert_eq!(
+      compile_script("forward 5 10\n"),
+      error(1, ScriptErrorKind::UnexpectedInput("10".to_string()))
+    );
+    assert_eq!(
+      compile_script("repeat 2\nforward 1\n}\n"),
+      error(1, ScriptErrorKind::MissingBrace("repeat".to_string()))
+    );
+    assert_eq!(
+      compile_script("macro m\n}\n"),
+      error(1, ScriptErrorKind::MissingBrace("macro".to_string()))
+    );
+    assert_eq!(
+      compile_script("up 5 {\n"),
+      error(1, ScriptErrorKind::UnexpectedInput("{".to_string()))
+    );
+    assert_eq!(
+      compile_script("repeat 2 {\nup 1\n} up 2\n"),
+      error(3, ScriptErrorKind::UnexpectedInput("up".to_string()))
+    );
+    assert_eq!(
+      compile_script("const A = 1 2\n"),
+      error(1, ScriptErrorKind::UnexpectedInput("2".to_string()))
+    );
+    assert_eq!(
+      compile_script("macro up {\nforward 9\n}\nup 1\n"),
+      error(1, ScriptErrorKind::ReservedName("up".to_string()))
+    );
+    for name in ["const", "repeat", "macro", "3", "-x"] {
+      assert_eq!(
+        compile_script(&format!("const {} = 1\n", name)),
+        error(1, ScriptErrorKind::ReservedName(name.to_string()))
+      );
+    }
+    assert_eq!(
+      compile_script("repeat 2147483647 {\nforward 1\n}\n"),
+      error(1, ScriptErrorKind::TooManyCommands)
+    );
+    assert_eq!(
+      compile_script("repeat 65536 {\nrepeat 65536 {\nforward 1\n}\n}\n"),
+      error(1, ScriptErrorKind::TooManyCommands)
+    );
+    assert_eq!(
+      compile_script("repeat 2147483647 {\n}\nup 1\n"),
+      Ok(vec![Command::Up(1)])
+    );
+    assert_eq!(
+      compile_script("const A = 1\nmacro A {\n}\n"),
+      error(2, ScriptErrorKind::DuplicateName("A".to_string()))
+    );
+  }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::{Command, Position, SteeringModel};
+
+const COLOURS: [&str; 4] = ["#2c7fb8", "#d95f0e", "#31a354", "#756bb1"];
+
+// `positions[0]` is the starting position and `positions[i + 1]` is where the
+// submarine is after the `i`th command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+  positions: Vec<Position>,
+}
+
+// Like `SteeringModel::run`, `record` panics when a position overflows and
+// `try_record` returns `None`.
+impl Trajectory {
+  pub fn try_record<M: SteeringModel + ?Sized>(
+    model: &M,
+    commands: &[Command],
+  ) -> Option<Trajectory> {
+    let mut positions = Vec::with_capacity(commands.len() + 1);
+    positions.push(Position::default());
+    for command in commands {
+      let position = *positions.last().expect("trajectory is empty");
+      positions.push(model.apply(position, command)?);
+    }
+
+    Some(Trajectory { positions })
+  }
+
+  pub fn record<M: SteeringModel + ?Sized>(model: &M, commands: &[Command]) -> Trajectory {
+    Trajectory::try_record(model, commands).expect("position overflowed")
+  }
+
+  pub fn positions(&self) -> &[Position] {
+    &self.positions
+  }
+
+  pub fn last(&self) -> Position {
+    *self.positions.last().expect("trajectory is empty")
+  }
+}
+
+pub fn write_trajectories_csv<W: Write>(
+  mut writer: W,
+  trajectories: &[(&str, &Trajectory)],
+) -> io::Result<()> {
+  writeln!(writer, "model,step,horizontal,depth,aim,lateral,drift")?;
+  for (name, trajectory) in trajectories {
+    for (step, position) in trajectory.positions().iter().enumerate() {
+      writeln!(
+        writer,
+        "{},{},{},{},{},{},{}",
+        name,
+        step,
+        position.horizontal,
+        position.depth,
+        position.aim,
+        position.lateral,
+        position.drift
+      )?;
+    }
+  }
+
+  Ok(())
+}
+
+// Each trajectory gets its own panel and scale, since the aim-based depths are
+// usually orders of magnitude larger than the naive ones. Depth grows downwards.
+pub fn trajectories_svg(trajectories: &[(&str, &Trajectory)], width: u32, height: u32) -> String {
+  let padding = 30.0;
+  let panel_height = f64::from(height) / trajectories.len().max(1) as f64;
+  let mut out = String::new();
+
+  writeln!(
+    out,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+    width, height, width, height
+  )
+  .expect("couldn't write to string");
+  writeln!(out, r#"  <rect width="100%" height="100%" fill="white"/>"#)
+    .expect("couldn't write to string");
+
+  for (panel, (name, trajectory)) in trajectories.iter().enumerate() {
+    let top = panel as f64 * panel_height;
+    let positions = trajectory.positions();
+    let (min_h, max_h) = positions.iter().fold((0, 0), |(min, max), p| {
+      (min.min(p.horizontal), max.max(p.horizontal))
+    });
+    let (min_d, max_d) = positions
+      .iter()
+      .fold((0, 0), |(min, max), p| (min.min(p.depth), max.max(p.depth)));
+    let span_h = (max_h - min_h).max(1) as f64;
+    let span_d = (max_d - min_d).max(1) as f64;
+    let x = |h: i64| padding + (h - min_h) as f64 / span_h * (f64::from(width) - 2.0 * padding);
+    let y = |d: i64| top + padding + (d - min_d) as f64 / span_d * (panel_height - 2.0 * padding);
+
+    let points: Vec<String> = positions
+      .iter()
+      .map(|p| format!("{:.2},{:.2}", x(p.horizontal), y(p.depth)))
+      .collect();
+    writeln!(
+      out,
+      r#"  <text x="{}" y="{:.2}" font-family="sans-serif" font-size="12">{} (depth {}..{}, horizontal {}..{})</text>"#,
+      padding,
+      top + padding - 10.0,
+      name,
+      min_d,
+      max_d,
+      min_h,
+      max_h
+    )
+    .expect("couldn't write to string");
+    writeln!(
+      out,
+      r#"  <polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+      points.join(" "),
+      COLOURS[panel % COLOURS.len()]
+    )
+    .expect("couldn't write to string");
+  }
+  writeln!(out, "</svg>").expect("couldn't write to string");
+
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{AimSteering, NaiveSteering};
+
+  const EXAMPLE: [Command; 6] = [
+    Command::Forward(5),
+    Command::Down(5),
+    Command::Forward(8),
+    Command::Up(3),
+    Command::Down(8),
+    Command::Forward(2),
+  ];
+
+  #[test]
+  fn record_trajectory_with_aim() {
+    let trajectory = Trajectory::record(&AimSteering, &EXAMPLE);
+    assert_eq!(trajectory.positions().len(), 7);
+    assert_eq!(
+      trajectory.positions()[3],
+      Position {
+        horizontal: 13,
+        depth: 40,
+        aim: 5,
+        ..Position::default()
+      }
+    );
+    assert_eq!(trajectory.last().product(), 900);
+  }
+
+  #[test]
+  fn trajectories_as_csv() {
+    let trajectory = Trajectory::record(&NaiveSteering, &EXAMPLE[..2]);
+    let mut csv = Vec::new();
+    write_trajectories_csv(&mut csv, &[("naive", &trajectory)]).unwrap();
+    assert_eq!(
+      String::from_utf8(csv).unwrap(),
+      "model,step,horizontal,depth,aim,lateral,drift\nnaive,0,0,0,0,0,0\nnaive,1,5,0,0,0,0\nnaive,2,5,5,0,0,0\n"
+    );
+  }
+
+  #[test]
+  fn trajectories_as_svg() {
+    let naive = Trajectory::record(&NaiveSteering, &EXAMPLE);
+    let aim = Trajectory::record(&AimSteering, &EXAMPLE);
+    let svg = trajectories_svg(&[("naive", &naive), ("aim", &aim)], 600, 400);
+    assert_eq!(svg.matches("<polyline").count(), 2);
+    assert!(svg.contains("aim (depth 0..60, horizontal 0..15)"));
+  }
+}
@@ -0,0 +1,99 @@
+use crate::Rule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Commonality {
+  MostCommon,
+  LeastCommon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+  PreferOne,
+  PreferZero,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+  MostSignificantFirst,
+  LeastSignificantFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitCriteria {
+  pub commonality: Commonality,
+  pub tie_break: TieBreak,
+  pub order: BitOrder,
+}
+
+impl BitCriteria {
+  pub const OXYGEN: BitCriteria = BitCriteria {
+    commonality: Commonality::MostCommon,
+    tie_break: TieBreak::PreferOne,
+    order: BitOrder::MostSignificantFirst,
+  };
+
+  pub const CO2: BitCriteria = BitCriteria {
+    commonality: Commonality::LeastCommon,
+    tie_break: TieBreak::PreferZero,
+    order: BitOrder::MostSignificantFirst,
+  };
+
+  // When only one bit value is left in a position it's kept whatever the
+  // criteria, so filtering never runs out of candidates.
+  pub fn rule(&self, zeros: u64, ones: u64) -> Rule {
+    if zeros == 0 || ones == 0 {
+      Rule::OnlyValue
+    } else if zeros == ones {
+      Rule::TieBreak(self.tie_break)
+    } else {
+      Rule::Commonality(self.commonality)
+    }
+  }
+
+  pub fn keeps_one(&self, zeros: u64, ones: u64) -> bool {
+    match self.rule(zeros, ones) {
+      Rule::OnlyValue => zeros == 0,
+      Rule::TieBreak(tie_break) => tie_break == TieBreak::PreferOne,
+      Rule::Commonality(Commonality::MostCommon) => ones > zeros,
+      Rule::Commonality(Commonality::LeastCommon) => ones < zeros,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+  pub name: String,
+  pub criteria: BitCriteria,
+}
+
+impl Rating {
+  pub fn new(name: impl Into<String>, criteria: BitCriteria) -> Rating {
+    Rating {
+      name: name.into(),
+      criteria,
+    }
+  }
+
+  pub fn oxygen() -> Rating {
+    Rating::new("oxygen", BitCriteria::OXYGEN)
+  }
+
+  pub fn co2() -> Rating {
+    Rating::new("co2", BitCriteria::CO2)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn criteria_choose_bits() {
+    assert!(BitCriteria::OXYGEN.keeps_one(3, 5));
+    assert!(BitCriteria::OXYGEN.keeps_one(4, 4));
+    assert!(!BitCriteria::CO2.keeps_one(4, 4));
+    assert!(!BitCriteria::CO2.keeps_one(3, 5));
+    assert!(BitCriteria::CO2.keeps_one(0, 5));
+    assert!(!BitCriteria::OXYGEN.keeps_one(2, 0));
+  }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use crate::{Commonality, TieBreak, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+  OnlyValue,
+  Commonality(Commonality),
+  TieBreak(TieBreak),
+}
+
+impl fmt::Display for Rule {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Rule::OnlyValue => write!(f, "only value"),
+      Rule::Commonality(Commonality::MostCommon) => write!(f, "most common"),
+      Rule::Commonality(Commonality::LeastCommon) => write!(f, "least common"),
+      Rule::TieBreak(TieBreak::PreferOne) => write!(f, "tie, prefer 1"),
+      Rule::TieBreak(TieBreak::PreferZero) => write!(f, "tie, prefer 0"),
+    }
+  }
+}
+
+// `bit` is the bit index counted from the least significant bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+  pub bit: usize,
+  pub zeros: u64,
+  pub ones: u64,
+  pub kept_one: bool,
+  pub rule: Rule,
+  pub survivors: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<W> {
+  pub rounds: Vec<Round>,
+  pub value: W,
+}
+
+impl<W: Word> fmt::Display for Trace<W> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(
+      f,
+      "{:>4} {:>8} {:>8} {:>4}  {:<14} {:>9}",
+      "bit", "zeros", "ones", "kept", "rule", "survivors"
+    )?;
+    for round in &self.rounds {
+      writeln!(
+        f,
+        "{:>4} {:>8} {:>8} {:>4}  {:<14} {:>9}",
+        round.bit,
+        round.zeros,
+        round.ones,
+        u8::from(round.kept_one),
+        round.rule.to_string(),
+        round.survivors
+      )?;
+    }
+    write!(f, "rating: {}", self.value.to_u128())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BitCriteria, Readings};
+
+  #[test]
+  fn trace_oxygen_example() {
+    let readings: Readings = Readings::from_lines(&[
+      "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001",
+      "00010", "01010",
+    ]);
+    let trace = readings.explain(&BitCriteria::OXYGEN);
+    assert_eq!(trace.value, 23);
+    assert_eq!(
+      trace.rounds[0],
+      Round {
+        bit: 4,
+        zeros: 5,
+        ones: 7,
+        kept_one: true,
+        rule: Rule::Commonality(Commonality::MostCommon),
+        survivors: 7,
+      }
+    );
+    assert_eq!(
+      trace
+        .rounds
+        .iter()
+        .map(|round| round.survivors)
+        .collect::<Vec<_>>(),
+      vec![7, 4, 3, 2, 1]
+    );
+    assert_eq!(trace.rounds[4].rule, Rule::TieBreak(TieBreak::PreferOne));
+  }
+
+  #[test]
+  fn render_trace_table() {
+    let readings: Readings = Readings::from_lines(&["10", "01", "00"]);
+    assert_eq!(
+      readings.explain(&BitCriteria::CO2).to_string(),
+      " bit    zeros     ones kept  rule           survivors
+   1        2        1    1  least common           1
+   0        1        0    0  only value             1
+rating: 2"
+    );
+  }
+}
@@ -0,0 +1,167 @@
+use crate::{Readings, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbour<W> {
+  pub value: W,
+  pub weight: u64,
+  pub distance: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster<W> {
+  pub values: Vec<W>,
+  pub weight: u64,
+}
+
+struct DisjointSet {
+  parents: Vec<usize>,
+}
+
+impl DisjointSet {
+  fn new(len: usize) -> DisjointSet {
+    DisjointSet {
+      parents: (0..len).collect(),
+    }
+  }
+
+  fn find(&mut self, mut idx: usize) -> usize {
+    while self.parents[idx] != idx {
+      self.parents[idx] = self.parents[self.parents[idx]];
+      idx = self.parents[idx];
+    }
+    idx
+  }
+
+  // The lower index stays the root so clusters keep the order their first
+  // reading appeared in.
+  fn union(&mut self, a: usize, b: usize) {
+    let (a, b) = (self.find(a), self.find(b));
+    self.parents[a.max(b)] = a.min(b);
+  }
+}
+
+impl<W: Word> Readings<W> {
+  // Every distinct reading tied for the smallest distance, in the order they
+  // first appeared. Bits of `pattern` beyond the report's width are ignored.
+  pub fn nearest(&self, pattern: W) -> Vec<Neighbour<W>> {
+    let pattern = match W::BITS - self.width() {
+      0 => pattern,
+      unused if unused == W::BITS => W::default(),
+      unused => pattern << unused >> unused,
+    };
+    let neighbours: Vec<Neighbour<W>> = self
+      .weighted()
+      .map(|(value, weight)| Neighbour {
+        value,
+        weight,
+        distance: value.distance(pattern),
+      })
+      .collect();
+    let closest = neighbours.iter().map(|neighbour| neighbour.distance).min();
+
+    neighbours
+      .into_iter()
+      .filter(|neighbour| Some(neighbour.distance) == closest)
+      .collect()
+  }
+
+  pub fn closest_to_gamma(&self) -> Vec<Neighbour<W>> {
+    self.nearest(self.gamma_and_epsilon().0)
+  }
+
+  // Readings are linked when they're within `within` bits of each other, so a
+  // cluster can span more than that end to end. Distinct values are compared
+  // pairwise, which is fine for the few thousand a weighted report has.
+  pub fn clusters(&self, within: u32) -> Vec<Cluster<W>> {
+    let values = self.values();
+    let mut set = DisjointSet::new(values.len());
+    for (idx, &value) in values.iter().enumerate() {
+      for (other, &other_value) in values.iter().enumerate().skip(idx + 1) {
+        if value.distance(other_value) <= within {
+          set.union(idx, other);
+        }
+      }
+    }
+
+    let mut clusters: Vec<Cluster<W>> = Vec::new();
+    let mut positions = vec![None; values.len()];
+    for (idx, (value, weight)) in self.weighted().enumerate() {
+      let root = set.find(idx);
+      let position = *positions[root].get_or_insert_with(|| {
+        clusters.push(Cluster {
+          values: Vec::new(),
+          weight: 0,
+        });
+        clusters.len() - 1
+      });
+      clusters[position].values.push(value);
+      clusters[position].weight += weight;
+    }
+
+    clusters
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn nearest_readings() {
+    let readings: Readings = Readings::from_lines(&["10110 x3", "10011", "00110", "11111"]);
+    assert_eq!(
+      readings.nearest(0b10010),
+      vec![
+        Neighbour {
+          value: 0b10110,
+          weight: 3,
+          distance: 1,
+        },
+        Neighbour {
+          value: 0b10011,
+          weight: 1,
+          distance: 1,
+        },
+      ]
+    );
+    assert_eq!(
+      readings.closest_to_gamma(),
+      vec![Neighbour {
+        value: 0b10110,
+        weight: 3,
+        distance: 0,
+      }]
+    );
+    assert_eq!(
+      readings.nearest(1 << 40 | 0b10010),
+      readings.nearest(0b10010)
+    );
+    let empty = Readings::<u64>::from_weighted(5, []).unwrap();
+    assert_eq!(empty.nearest(0), vec![]);
+  }
+
+  #[test]
+  fn cluster_within_distance() {
+    let readings: Readings =
+      Readings::from_lines(&["00000 x5", "11111", "00001", "11110 x2", "00011", "10101"]);
+    assert_eq!(
+      readings.clusters(1),
+      vec![
+        Cluster {
+          values: vec![0b00000, 0b00001, 0b00011],
+          weight: 7,
+        },
+        Cluster {
+          values: vec![0b11111, 0b11110],
+          weight: 3,
+        },
+        Cluster {
+          values: vec![0b10101],
+          weight: 1,
+        },
+      ]
+    );
+    assert_eq!(readings.clusters(0).len(), 6);
+    assert_eq!(readings.clusters(5).len(), 1);
+  }
+}
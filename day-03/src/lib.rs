@@ -1,10 +1,27 @@
-use std::collections::BTreeMap;
 use std::{
   fs::File,
   io::{prelude::*, BufReader},
   path::Path,
 };
 
+mod criteria;
+mod explain;
+mod hamming;
+mod parse;
+mod readings;
+mod report;
+mod trie;
+
+use parse::split_reading;
+
+pub use criteria::{BitCriteria, BitOrder, Commonality, Rating, TieBreak};
+pub use explain::{Round, Rule, Trace};
+pub use hamming::{Cluster, Neighbour};
+pub use parse::{parse_readings, ReadingError, ReadingErrorKind, WidthPolicy};
+pub use readings::{Readings, Word};
+pub use report::{ColumnCounts, DiagnosticReport};
+pub use trie::BitTrie;
+
 pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
   let file = File::open(filename).expect("file doesn't exist");
   let reader = BufReader::new(file);
@@ -14,157 +31,52 @@ pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
     .collect()
 }
 
-fn bits_from_lines(lines: Vec<String>) -> Vec<Vec<u8>> {
-  let init: Vec<Vec<u8>> = Vec::new();
-  lines.iter().fold(init, |columns, line| {
-    let parts = line.chars();
-    let mut result = columns.clone();
-    for (idx, column) in parts.enumerate() {
-      let bit = column.to_digit(10).expect("not a number") as u8;
-      if result.len() <= idx {
-        result.push(Vec::new());
-      }
-      result[idx].push(bit)
-    }
-
-    result
-  })
+fn report_width<S: AsRef<str>>(lines: &[S]) -> usize {
+  lines
+    .iter()
+    .map(|line| split_reading(line.as_ref()).0.len())
+    .max()
+    .unwrap_or(0)
 }
 
-fn find_occurrences_in_column(column: Vec<u8>) -> BTreeMap<u8, i32> {
-  let mut counts = BTreeMap::new();
-  for bit in column {
-    *counts.entry(bit).or_insert(0) += 1;
-  }
-
-  counts
-}
-
-fn calculate_gamma_and_epsilon_rate(bits: Vec<Vec<u8>>) -> (u32, u32) {
-  let mut gamma_rate = String::new();
-  let mut epsilon_rate = String::new();
-
-  for column in bits {
-    let counts = find_occurrences_in_column(column);
-
-    gamma_rate += &counts
-      .clone()
-      .into_iter()
-      .max_by_key(|&(_, count)| count)
-      .expect("empty")
-      .0
-      .to_string();
-
-    epsilon_rate += &counts
-      .clone()
-      .into_iter()
-      .min_by_key(|&(_, count)| count)
-      .expect("empty")
-      .0
-      .to_string();
-  }
-
-  (
-    isize::from_str_radix(&gamma_rate, 2).expect("not a binary number") as u32,
-    isize::from_str_radix(&epsilon_rate, 2).expect("not a binary number") as u32,
-  )
+pub fn parse_report<S: AsRef<str>>(
+  lines: &[S],
+  policy: WidthPolicy,
+) -> Result<DiagnosticReport, ReadingError> {
+  Ok(if report_width(lines) <= 64 {
+    DiagnosticReport::new(&parse_readings::<u64, _>(lines, policy)?)
+  } else {
+    DiagnosticReport::new(&parse_readings::<u128, _>(lines, policy)?)
+  })
 }
 
-fn calculate_oxygen_and_co2_rating(lines: Vec<String>) -> (u32, u32) {
-  let mut oxygen_considerations = lines.clone();
-  let mut co2_considerations = lines.clone();
-
-  enum ConsiderationMode {
-    MostCommon,
-    LeastCommon,
-  }
-
-  let filter_considerations =
-    |considerations: Vec<String>, idx: usize, mode: ConsiderationMode| -> Vec<String> {
-      let columns = bits_from_lines(considerations.clone());
-      let counts = find_occurrences_in_column(columns[idx].clone());
-      let most_common = &counts
-        .clone()
-        .into_iter()
-        .max_by_key(|&(_, count)| count)
-        .expect("empty")
-        .0
-        .to_string();
-      let least_common = &counts
-        .clone()
-        .into_iter()
-        .min_by_key(|&(_, count)| count)
-        .expect("empty")
-        .0
-        .to_string();
-      let mut bit: &String;
-
-      match mode {
-        ConsiderationMode::MostCommon => {
-          bit = most_common;
-          if most_common == least_common && most_common == "0" {
-            bit = least_common;
-          }
-        }
-        ConsiderationMode::LeastCommon => {
-          bit = least_common;
-          if most_common == least_common && least_common == "1" {
-            bit = most_common;
-          }
-        }
-      }
-
-      considerations
-        .into_iter()
-        .filter(|l| {
-          l.chars().nth(idx).expect("could not parse number")
-            == bit.parse::<char>().expect("could not parse bit")
-        })
-        .collect()
-    };
-
-  let mut idx = 0;
-  while oxygen_considerations.len() > 1 {
-    oxygen_considerations =
-      filter_considerations(oxygen_considerations, idx, ConsiderationMode::MostCommon);
-    idx += 1;
-  }
-  let mut idx = 0;
-  while co2_considerations.len() > 1 {
-    co2_considerations =
-      filter_considerations(co2_considerations, idx, ConsiderationMode::LeastCommon);
-    idx += 1;
-  }
-
-  (
-    isize::from_str_radix(&oxygen_considerations[0], 2).expect("not a binary number") as u32,
-    isize::from_str_radix(&co2_considerations[0], 2).expect("not a binary number") as u32,
-  )
+pub fn diagnostic_report<S: AsRef<str>>(lines: &[S]) -> DiagnosticReport {
+  parse_report(lines, WidthPolicy::Reject).unwrap_or_else(|err| panic!("{}", err))
 }
 
-pub fn calculate_power_consumption(lines: Vec<String>) -> u32 {
-  let (gamma_rate, epsilon_rate) = calculate_gamma_and_epsilon_rate(bits_from_lines(lines));
-  gamma_rate * epsilon_rate
+// Reports wider than 64 bits can have products that don't fit in 128; the
+// factors are on the `DiagnosticReport`.
+pub fn calculate_power_consumption(lines: Vec<String>) -> Option<u128> {
+  diagnostic_report(&lines).power_consumption()
 }
 
-pub fn calculate_life_support_rating(lines: Vec<String>) -> u32 {
-  let (oxygen_rating, co2_rating) = calculate_oxygen_and_co2_rating(lines);
-  oxygen_rating * co2_rating
+pub fn calculate_life_support_rating(lines: Vec<String>) -> Option<u128> {
+  diagnostic_report(&lines).life_support_rating()
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+
+  const EXAMPLE: [&str; 12] = [
+    "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001",
+    "00010", "01010",
+  ];
+
   #[test]
   fn calculate_gamma_and_epsilon_rate_example() {
     assert_eq!(
-      calculate_gamma_and_epsilon_rate(vec![
-        vec![0, 1, 1, 1, 1, 0, 0, 1, 1, 1, 0, 0],
-        vec![0, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 1],
-        vec![1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0],
-        vec![0, 1, 1, 1, 0, 1, 1, 0, 0, 0, 1, 1],
-        vec![0, 0, 0, 1, 1, 1, 1, 0, 0, 1, 0, 0]
-      ]),
+      Readings::<u64>::from_lines(&EXAMPLE).gamma_and_epsilon(),
       (22, 9)
     );
   }
@@ -186,31 +98,58 @@ mod tests {
         "00010".to_string(),
         "01010".to_string(),
       ]),
-      198
+      Some(198)
     );
   }
 
   #[test]
   fn calculate_oxygen_and_co2_rating_example() {
     assert_eq!(
-      calculate_oxygen_and_co2_rating(vec![
-        "00100".to_string(),
-        "11110".to_string(),
-        "10110".to_string(),
-        "10111".to_string(),
-        "10101".to_string(),
-        "01111".to_string(),
-        "00111".to_string(),
-        "11100".to_string(),
-        "10000".to_string(),
-        "11001".to_string(),
-        "00010".to_string(),
-        "01010".to_string(),
-      ]),
+      Readings::<u64>::from_lines(&EXAMPLE).oxygen_and_co2(),
       (23, 10)
     );
   }
 
+  #[test]
+  fn diagnostic_report_example() {
+    let report = diagnostic_report(&EXAMPLE);
+    assert_eq!(
+      (report.gamma, report.epsilon, report.oxygen, report.co2),
+      (22, 9, 23, 10)
+    );
+    assert_eq!(report.columns[0], ColumnCounts { zeros: 5, ones: 7 });
+    assert!(report.tied_columns.is_empty());
+  }
+
+  #[test]
+  fn wide_products_dont_panic() {
+    let lines = vec!["1".repeat(100), format!("1{}", "0".repeat(99))];
+    assert_eq!(calculate_power_consumption(lines.clone()), None);
+    let report = diagnostic_report(&lines);
+    assert_eq!((report.gamma, report.epsilon), ((1 << 100) - 1, 1 << 99));
+    assert_eq!(
+      calculate_life_support_rating(vec!["1".repeat(65), "0".repeat(65)]),
+      Some(0)
+    );
+  }
+
+  #[test]
+  fn empty_reports_are_errors() {
+    assert_eq!(
+      parse_report::<&str>(&[], WidthPolicy::Reject).map_err(|error| error.kind),
+      Err(ReadingErrorKind::EmptyReport)
+    );
+  }
+
+  #[test]
+  fn weighted_report_matches_expanded_report() {
+    let expanded = [
+      "10110", "10110", "10110", "01011", "00111", "00111", "11100", "10110",
+    ];
+    let weighted = ["10110 x3", "01011", "00111 x2", "11100", "10110 x1"];
+    assert_eq!(diagnostic_report(&weighted), diagnostic_report(&expanded));
+  }
+
   #[test]
   fn calculate_life_support_rating_example() {
     assert_eq!(
@@ -228,7 +167,7 @@ mod tests {
         "00010".to_string(),
         "01010".to_string(),
       ]),
-      230
+      Some(230)
     );
   }
 }
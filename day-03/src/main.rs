@@ -1,15 +1,100 @@
-use std::env;
+use std::{env, process};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
-    let columns = day_03::lines_from_file(filename);
-    println!(
-        "Part 1: {:?}",
-        day_03::calculate_power_consumption(columns.clone())
+    let mut policy = day_03::WidthPolicy::Reject;
+    let mut explain = false;
+    let mut nearest = None;
+    let mut clusters = None;
+    let mut options = args.iter().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--widths" => {
+                policy = match options.next().expect("missing width policy").as_str() {
+                    "reject" => day_03::WidthPolicy::Reject,
+                    "left-pad" => day_03::WidthPolicy::LeftPad,
+                    "right-pad" => day_03::WidthPolicy::RightPad,
+                    other => panic!("unknown width policy {}", other),
+                }
+            }
+            "--explain" => explain = true,
+            "--nearest" => nearest = Some(options.next().expect("missing pattern")),
+            "--clusters" => {
+                clusters = Some(
+                    options
+                        .next()
+                        .expect("missing cluster distance")
+                        .parse::<u32>()
+                        .expect("not a number"),
+                )
+            }
+            _ => panic!("unknown option {}", option),
+        }
+    }
+
+    let lines = day_03::lines_from_file(filename);
+    let report = day_03::parse_report(&lines, policy).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let part = |name: &str, product: Option<u128>, (a, b): (u128, u128)| match product {
+        Some(product) => println!("{}: {:?}", name, product),
+        None => println!("{}: {} * {} (doesn't fit in 128 bits)", name, a, b),
+    };
+    part(
+        "Part 1",
+        report.power_consumption(),
+        (report.gamma, report.epsilon),
     );
-    println!(
-        "Part 2: {:?}",
-        day_03::calculate_life_support_rating(columns.clone())
+    part(
+        "Part 2",
+        report.life_support_rating(),
+        (report.oxygen, report.co2),
     );
+
+    if !explain && nearest.is_none() && clusters.is_none() {
+        return;
+    }
+
+    let readings: day_03::Readings<u128> =
+        day_03::parse_readings(&lines, policy).expect("report was already parsed");
+    let binary = |value: u128| format!("{:0width$b}", value, width = readings.width());
+    if explain {
+        for rating in [day_03::Rating::oxygen(), day_03::Rating::co2()] {
+            println!("\n{}:\n{}", rating.name, readings.explain(&rating.criteria));
+        }
+    }
+
+    if let Some(pattern) = nearest {
+        if pattern.len() != readings.width() {
+            eprintln!(
+                "pattern {} has {} bits but the report has {}",
+                pattern,
+                pattern.len(),
+                readings.width()
+            );
+            process::exit(1);
+        }
+        if !pattern.chars().all(|c| c == '0' || c == '1') {
+            eprintln!("pattern {} is not a binary number", pattern);
+            process::exit(1);
+        }
+        let pattern = u128::from_str_radix(pattern, 2).expect("pattern fits in 128 bits");
+        for neighbour in readings.nearest(pattern) {
+            println!(
+                "{} x{} is {} bits from {}",
+                binary(neighbour.value),
+                neighbour.weight,
+                neighbour.distance,
+                binary(pattern)
+            );
+        }
+    }
+    if let Some(within) = clusters {
+        for cluster in readings.clusters(within) {
+            let values: Vec<String> = cluster.values.iter().map(|&value| binary(value)).collect();
+            println!("x{}: {}", cluster.weight, values.join(", "));
+        }
+    }
 }
@@ -0,0 +1,266 @@
+use std::{error::Error, fmt};
+
+use crate::{Readings, Word};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WidthPolicy {
+  #[default]
+  Reject,
+  LeftPad,
+  RightPad,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadingErrorKind {
+  InvalidBit(char),
+  InconsistentWidth { expected: usize, found: usize },
+  TooWide { bits: usize },
+  InvalidWeight(String),
+  TooManyReadings,
+  EmptyReport,
+}
+
+// Lines and columns are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadingError {
+  pub line: usize,
+  pub column: usize,
+  pub kind: ReadingErrorKind,
+}
+
+impl fmt::Display for ReadingError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "line {}, column {}: ", self.line, self.column)?;
+    match &self.kind {
+      ReadingErrorKind::InvalidBit(c) => write!(f, "{:?} is not a binary digit", c),
+      ReadingErrorKind::InconsistentWidth { expected, found } => write!(
+        f,
+        "reading has {} bits but the report has {}",
+        found, expected
+      ),
+      ReadingErrorKind::TooWide { bits } => write!(f, "report is too wide for {} bits", bits),
+      ReadingErrorKind::TooManyReadings => {
+        write!(f, "report has too many readings to count in 64 bits")
+      }
+      ReadingErrorKind::InvalidWeight(text) => {
+        write!(f, "{:?} is not a multiplicity like \"x42\"", text)
+      }
+      ReadingErrorKind::EmptyReport => write!(f, "report has no readings"),
+    }
+  }
+}
+
+impl Error for ReadingError {}
+
+// A reading can be followed by a multiplicity, as in `010110 x42`, and
+// counts as that many identical readings.
+pub(crate) fn split_reading(line: &str) -> (&str, Option<&str>) {
+  match line.split_once(char::is_whitespace) {
+    Some((bits, rest)) if !rest.trim().is_empty() => (bits, Some(rest)),
+    Some((bits, _)) => (bits, None),
+    None => (line, None),
+  }
+}
+
+// With padding, every reading is padded with zeros to the widest one: on the
+// left keeps its value, on the right keeps its leading bits lined up.
+pub fn parse_readings<W: Word, S: AsRef<str>>(
+  lines: &[S],
+  policy: WidthPolicy,
+) -> Result<Readings<W>, ReadingError> {
+  let error = |line: usize, column, kind| ReadingError {
+    line: line + 1,
+    column,
+    kind,
+  };
+  let bits_len = |line: &S| split_reading(line.as_ref()).0.len();
+  if lines.is_empty() {
+    return Err(error(0, 1, ReadingErrorKind::EmptyReport));
+  }
+
+  let width = match policy {
+    WidthPolicy::Reject => lines.first().map_or(0, bits_len),
+    WidthPolicy::LeftPad | WidthPolicy::RightPad => lines.iter().map(bits_len).max().unwrap_or(0),
+  };
+  if width > W::BITS {
+    let line = lines
+      .iter()
+      .position(|line| bits_len(line) > W::BITS)
+      .expect("a line is too wide");
+    return Err(error(
+      line,
+      W::BITS + 1,
+      ReadingErrorKind::TooWide { bits: W::BITS },
+    ));
+  }
+
+  let mut readings = Vec::with_capacity(lines.len());
+  let mut total: u64 = 0;
+  for (idx, line) in lines.iter().enumerate() {
+    let (bits, weight) = split_reading(line.as_ref());
+    if policy == WidthPolicy::Reject && bits.len() != width {
+      return Err(error(
+        idx,
+        bits.len().min(width) + 1,
+        ReadingErrorKind::InconsistentWidth {
+          expected: width,
+          found: bits.len(),
+        },
+      ));
+    }
+
+    let mut value = W::default();
+    for (column, c) in bits.chars().enumerate() {
+      value = match c {
+        '0' => value << 1,
+        '1' => (value << 1) | W::ONE,
+        _ => return Err(error(idx, column + 1, ReadingErrorKind::InvalidBit(c))),
+      };
+    }
+    if policy == WidthPolicy::RightPad {
+      value = value << (width - bits.len());
+    }
+
+    let (weight, column) = match weight {
+      Some(rest) => {
+        let text = rest.trim();
+        let column = bits.len() + rest.len() - rest.trim_start().len() + 2;
+        let weight = text
+          .strip_prefix('x')
+          .and_then(|count| count.parse::<u64>().ok())
+          .filter(|&count| count > 0)
+          .ok_or_else(|| {
+            error(
+              idx,
+              column,
+              ReadingErrorKind::InvalidWeight(text.to_string()),
+            )
+          })?;
+        (weight, column)
+      }
+      None => (1, 1),
+    };
+    total = total
+      .checked_add(weight)
+      .ok_or_else(|| error(idx, column, ReadingErrorKind::TooManyReadings))?;
+    readings.push((value, weight));
+  }
+
+  Ok(Readings::from_weighted(width, readings).expect("width, values and total weight were checked"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reject_invalid_bits() {
+    assert_eq!(
+      parse_readings::<u64, _>(&["0101", "0121"], WidthPolicy::Reject),
+      Err(ReadingError {
+        line: 2,
+        column: 3,
+        kind: ReadingErrorKind::InvalidBit('2'),
+      })
+    );
+  }
+
+  #[test]
+  fn reject_ragged_reports() {
+    let error = parse_readings::<u64, _>(&["0101", "01", "010"], WidthPolicy::Reject).unwrap_err();
+    assert_eq!(
+      error,
+      ReadingError {
+        line: 2,
+        column: 3,
+        kind: ReadingErrorKind::InconsistentWidth {
+          expected: 4,
+          found: 2
+        },
+      }
+    );
+    assert_eq!(
+      error.to_string(),
+      "line 2, column 3: reading has 2 bits but the report has 4"
+    );
+  }
+
+  #[test]
+  fn reject_empty_reports() {
+    let error = parse_readings::<u64, &str>(&[], WidthPolicy::Reject).unwrap_err();
+    assert_eq!(
+      error,
+      ReadingError {
+        line: 1,
+        column: 1,
+        kind: ReadingErrorKind::EmptyReport,
+      }
+    );
+    assert_eq!(
+      error.to_string(),
+      "line 1, column 1: report has no readings"
+    );
+  }
+
+  #[test]
+  fn pad_ragged_reports() {
+    let lines = ["0101", "11", "1"];
+    let left = parse_readings::<u64, _>(&lines, WidthPolicy::LeftPad).unwrap();
+    assert_eq!(left.width(), 4);
+    assert_eq!(left.values(), [0b0101, 0b0011, 0b0001]);
+    let right = parse_readings::<u64, _>(&lines, WidthPolicy::RightPad).unwrap();
+    assert_eq!(right.values(), [0b0101, 0b1100, 0b1000]);
+  }
+
+  #[test]
+  fn reject_reports_too_wide_for_the_word() {
+    let lines = ["1".to_string(), "1".repeat(65)];
+    assert_eq!(
+      parse_readings::<u64, _>(&lines, WidthPolicy::LeftPad),
+      Err(ReadingError {
+        line: 2,
+        column: 65,
+        kind: ReadingErrorKind::TooWide { bits: 64 },
+      })
+    );
+  }
+
+  #[test]
+  fn parse_weighted_readings() {
+    let readings = parse_readings::<u64, _>(
+      &["010110 x42", "111000", "010110  x8", "111000 x1"],
+      WidthPolicy::Reject,
+    )
+    .unwrap();
+    assert_eq!(readings.values(), [0b010110, 0b111000]);
+    assert_eq!(readings.weights(), [50, 2]);
+  }
+
+  #[test]
+  fn reject_invalid_weights() {
+    let error = |column, text: &str| {
+      Err(ReadingError {
+        line: 2,
+        column,
+        kind: ReadingErrorKind::InvalidWeight(text.to_string()),
+      })
+    };
+    assert_eq!(
+      parse_readings::<u64, _>(&["0101", "0110 42"], WidthPolicy::Reject),
+      error(6, "42")
+    );
+    assert_eq!(
+      parse_readings::<u64, _>(&["0101", "0110  x0"], WidthPolicy::Reject),
+      error(7, "x0")
+    );
+    let lines = ["0101 x1".to_string(), format!("0110 x{}", u64::MAX)];
+    assert_eq!(
+      parse_readings::<u64, _>(&lines, WidthPolicy::Reject),
+      Err(ReadingError {
+        line: 2,
+        column: 6,
+        kind: ReadingErrorKind::TooManyReadings,
+      })
+    );
+  }
+}
@@ -0,0 +1,347 @@
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  hash::Hash,
+  ops::{BitOr, Shl, Shr},
+};
+
+use crate::{parse_readings, BitCriteria, BitTrie, Rating, Trace, WidthPolicy};
+
+pub trait Word:
+  Copy
+  + Debug
+  + Default
+  + Eq
+  + Ord
+  + Hash
+  + BitOr<Output = Self>
+  + Shl<usize, Output = Self>
+  + Shr<usize, Output = Self>
+{
+  const BITS: usize;
+  const ONE: Self;
+
+  fn bit(self, idx: usize) -> bool;
+  fn distance(self, other: Self) -> u32;
+  fn to_u128(self) -> u128;
+}
+
+macro_rules! impl_word {
+  ($($t:ty),*) => {
+    $(
+      impl Word for $t {
+        const BITS: usize = <$t>::BITS as usize;
+        const ONE: Self = 1;
+
+        fn bit(self, idx: usize) -> bool {
+          (self >> idx) & 1 == 1
+        }
+
+        fn distance(self, other: Self) -> u32 {
+          (self ^ other).count_ones()
+        }
+
+        fn to_u128(self) -> u128 {
+          self as u128
+        }
+      }
+    )*
+  };
+}
+
+impl_word!(u64, u128);
+
+// Column 0 is the leftmost character of a reading, which is its most
+// significant bit. Each distinct value is stored once with the number of
+// times it was read, in the order it first appeared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readings<W = u64> {
+  width: usize,
+  values: Vec<W>,
+  weights: Vec<u64>,
+}
+
+impl<W: Word> Readings<W> {
+  // Readings with a weight of 0 are left out. `None` means `width` doesn't fit
+  // in `W`, a value has bits set beyond `width`, or the total weight doesn't
+  // fit in a `u64`.
+  pub fn from_weighted(
+    width: usize,
+    readings: impl IntoIterator<Item = (W, u64)>,
+  ) -> Option<Readings<W>> {
+    let mut positions = HashMap::new();
+    let mut values = Vec::new();
+    let mut weights = Vec::new();
+    let mut total: u64 = 0;
+    if width > W::BITS {
+      return None;
+    }
+    for (value, weight) in readings {
+      if width < W::BITS && value >> width != W::default() {
+        return None;
+      }
+      if weight == 0 {
+        continue;
+      }
+      total = total.checked_add(weight)?;
+      let idx = *positions.entry(value).or_insert_with(|| {
+        values.push(value);
+        weights.push(0);
+        values.len() - 1
+      });
+      weights[idx] += weight;
+    }
+
+    Some(Readings {
+      width,
+      values,
+      weights,
+    })
+  }
+
+  pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Readings<W> {
+    parse_readings(lines, WidthPolicy::Reject).unwrap_or_else(|err| panic!("{}", err))
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn values(&self) -> &[W] {
+    &self.values
+  }
+
+  pub fn weights(&self) -> &[u64] {
+    &self.weights
+  }
+
+  pub fn weighted(&self) -> impl Iterator<Item = (W, u64)> + '_ {
+    self
+      .values
+      .iter()
+      .copied()
+      .zip(self.weights.iter().copied())
+  }
+
+  fn bit_in_column(&self, value: W, column: usize) -> bool {
+    value.bit(self.width - 1 - column)
+  }
+
+  // Chunks of 64 readings that were each read once are counted a column at a
+  // time with popcount; other chunks add up their weights bit by bit.
+  pub fn column_ones(&self) -> Vec<u64> {
+    let mut ones = vec![0; self.width];
+    for (values, weights) in self.values.chunks(64).zip(self.weights.chunks(64)) {
+      if weights.iter().all(|&weight| weight == 1) {
+        for (column, count) in ones.iter_mut().enumerate() {
+          let slice = values
+            .iter()
+            .enumerate()
+            .fold(0u64, |slice, (idx, &value)| {
+              slice | (u64::from(self.bit_in_column(value, column)) << idx)
+            });
+          *count += u64::from(slice.count_ones());
+        }
+        continue;
+      }
+
+      for (&value, &weight) in values.iter().zip(weights) {
+        for (column, count) in ones.iter_mut().enumerate() {
+          if self.bit_in_column(value, column) {
+            *count += weight;
+          }
+        }
+      }
+    }
+
+    ones
+  }
+
+  pub fn len(&self) -> u64 {
+    self.weights.iter().sum()
+  }
+
+  pub fn distinct(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  // Ties make gamma 1 and epsilon 0. A column with only one bit value in it
+  // gives that value to both.
+  pub fn gamma_and_epsilon(&self) -> (W, W) {
+    let total = self.len();
+    self
+      .column_ones()
+      .into_iter()
+      .fold((W::default(), W::default()), |(gamma, epsilon), ones| {
+        let zeros = total - ones;
+        let gamma_bit = ones >= zeros && ones > 0;
+        let epsilon_bit = zeros == 0 || (ones < zeros && ones > 0);
+        (push_bit(gamma, gamma_bit), push_bit(epsilon, epsilon_bit))
+      })
+  }
+
+  pub fn rating(&self, criteria: &BitCriteria) -> W {
+    BitTrie::with_order(self, criteria.order).rating(criteria)
+  }
+
+  pub fn explain(&self, criteria: &BitCriteria) -> Trace<W> {
+    BitTrie::with_order(self, criteria.order).trace(criteria)
+  }
+
+  // Ratings filtering in the same bit order share one trie.
+  pub fn ratings<'a>(&self, ratings: &'a [Rating]) -> Vec<(&'a str, W)> {
+    let mut tries: Vec<BitTrie<W>> = Vec::new();
+    ratings
+      .iter()
+      .map(|rating| {
+        let order = rating.criteria.order;
+        let idx = match tries.iter().position(|trie| trie.order() == order) {
+          Some(idx) => idx,
+          None => {
+            tries.push(BitTrie::with_order(self, order));
+            tries.len() - 1
+          }
+        };
+        (rating.name.as_str(), tries[idx].rating(&rating.criteria))
+      })
+      .collect()
+  }
+
+  pub fn oxygen_and_co2(&self) -> (W, W) {
+    let trie = BitTrie::new(self);
+    (trie.oxygen_rating(), trie.co2_rating())
+  }
+
+  pub fn power_consumption(&self) -> Option<u128> {
+    let (gamma, epsilon) = self.gamma_and_epsilon();
+    gamma.to_u128().checked_mul(epsilon.to_u128())
+  }
+
+  pub fn life_support_rating(&self) -> Option<u128> {
+    let (oxygen, co2) = self.oxygen_and_co2();
+    oxygen.to_u128().checked_mul(co2.to_u128())
+  }
+}
+
+fn push_bit<W: Word>(value: W, bit: bool) -> W {
+  if bit {
+    (value << 1) | W::ONE
+  } else {
+    value << 1
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::BitOrder;
+
+  #[test]
+  fn pack_readings() {
+    let readings: Readings = Readings::from_lines(&["00100", "11110", "10110"]);
+    assert_eq!(readings.width(), 5);
+    assert_eq!(readings.values(), [4, 30, 22]);
+    assert_eq!(readings.column_ones(), vec![2, 1, 3, 2, 0]);
+  }
+
+  #[test]
+  fn column_counts_span_chunks() {
+    let count = |readings: &Readings| {
+      (0..8)
+        .map(|column| {
+          readings
+            .weighted()
+            .filter(|&(value, _)| value >> (7 - column) & 1 == 1)
+            .map(|(_, weight)| weight)
+            .sum::<u64>()
+        })
+        .collect::<Vec<u64>>()
+    };
+    let unit = Readings::from_weighted(8, (0..150).map(|value| (value, 1))).unwrap();
+    assert_eq!(unit.distinct(), 150);
+    assert_eq!(unit.column_ones(), count(&unit));
+
+    let mixed = Readings::from_weighted(
+      8,
+      (0..150).map(|value| (value, if value < 64 { 1 } else { value })),
+    )
+    .unwrap();
+    assert_eq!(mixed.column_ones(), count(&mixed));
+  }
+
+  #[test]
+  fn wide_readings() {
+    let lines = vec!["1".repeat(100), format!("1{}", "0".repeat(99))];
+    let readings: Readings<u128> = Readings::from_lines(&lines);
+    let (gamma, epsilon) = readings.gamma_and_epsilon();
+    assert_eq!(gamma, (1u128 << 100) - 1);
+    assert_eq!(epsilon, 1u128 << 99);
+    assert_eq!(readings.power_consumption(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "report is too wide for 64 bits")]
+  fn too_wide_for_u64() {
+    let _: Readings<u64> = Readings::from_lines(&["1".repeat(65)]);
+  }
+
+  #[test]
+  fn named_ratings() {
+    let readings: Readings = Readings::from_lines(&["100", "110", "011", "001"]);
+    let lsb_oxygen = BitCriteria {
+      order: BitOrder::LeastSignificantFirst,
+      ..BitCriteria::OXYGEN
+    };
+    let ratings = [
+      Rating::oxygen(),
+      Rating::co2(),
+      Rating::new("lsb-oxygen", lsb_oxygen),
+    ];
+    assert_eq!(
+      readings.ratings(&ratings),
+      vec![("oxygen", 0b110), ("co2", 0b001), ("lsb-oxygen", 0b011)]
+    );
+  }
+
+  #[test]
+  fn weighted_column_counts() {
+    let readings: Readings = Readings::from_weighted(
+      3,
+      [
+        (0b101, 42),
+        (0b011, 3),
+        (0b111, 0),
+        (0b101, 1),
+        (0b110, u64::from(u32::MAX)),
+      ],
+    )
+    .unwrap();
+    assert_eq!(readings.values(), [0b101, 0b011, 0b110]);
+    assert_eq!(readings.weights(), [43, 3, u64::from(u32::MAX)]);
+    assert_eq!(readings.len(), 46 + u64::from(u32::MAX));
+    assert_eq!(
+      readings.column_ones(),
+      vec![43 + u64::from(u32::MAX), 3 + u64::from(u32::MAX), 46]
+    );
+  }
+
+  #[test]
+  fn weighted_edge_cases() {
+    let empty: Readings = Readings::from_weighted(3, [(0b101, 0)]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+    assert_eq!(
+      Readings::<u64>::from_weighted(3, [(0b101, u64::MAX), (0b011, 1)]),
+      None
+    );
+    assert_eq!(Readings::<u64>::from_weighted(65, [(0, 1)]), None);
+    assert_eq!(Readings::<u64>::from_weighted(3, [(0b1000, 1)]), None);
+    assert_eq!(Readings::<u64>::from_weighted(3, [(0b1000, 0)]), None);
+    let full = Readings::<u64>::from_weighted(64, [(u64::MAX, 1)]).unwrap();
+    assert_eq!(full.column_ones(), vec![1; 64]);
+  }
+}
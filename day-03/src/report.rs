@@ -0,0 +1,91 @@
+use crate::{Readings, Word};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnCounts {
+  pub zeros: u64,
+  pub ones: u64,
+}
+
+impl ColumnCounts {
+  pub fn is_tied(&self) -> bool {
+    self.zeros == self.ones
+  }
+}
+
+// Ratings are widened to u128 so reports of any supported width look the
+// same. Columns are numbered from 0 at the most significant bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticReport {
+  pub width: usize,
+  pub gamma: u128,
+  pub epsilon: u128,
+  pub oxygen: u128,
+  pub co2: u128,
+  pub columns: Vec<ColumnCounts>,
+  pub tied_columns: Vec<usize>,
+}
+
+impl DiagnosticReport {
+  pub fn new<W: Word>(readings: &Readings<W>) -> DiagnosticReport {
+    let total = readings.len();
+    let columns: Vec<ColumnCounts> = readings
+      .column_ones()
+      .into_iter()
+      .map(|ones| ColumnCounts {
+        zeros: total - ones,
+        ones,
+      })
+      .collect();
+    let tied_columns = columns
+      .iter()
+      .enumerate()
+      .filter(|(_, counts)| counts.is_tied())
+      .map(|(column, _)| column)
+      .collect();
+    let (gamma, epsilon) = readings.gamma_and_epsilon();
+    let (oxygen, co2) = readings.oxygen_and_co2();
+
+    DiagnosticReport {
+      width: readings.width(),
+      gamma: gamma.to_u128(),
+      epsilon: epsilon.to_u128(),
+      oxygen: oxygen.to_u128(),
+      co2: co2.to_u128(),
+      columns,
+      tied_columns,
+    }
+  }
+
+  pub fn power_consumption(&self) -> Option<u128> {
+    self.gamma.checked_mul(self.epsilon)
+  }
+
+  pub fn life_support_rating(&self) -> Option<u128> {
+    self.oxygen.checked_mul(self.co2)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn report_with_ties() {
+    let readings: Readings = Readings::from_lines(&["101", "011", "110", "000"]);
+    let report = DiagnosticReport::new(&readings);
+    assert_eq!(
+      report,
+      DiagnosticReport {
+        width: 3,
+        gamma: 0b111,
+        epsilon: 0b000,
+        oxygen: 0b110,
+        co2: 0b000,
+        columns: vec![ColumnCounts { zeros: 2, ones: 2 }; 3],
+        tied_columns: vec![0, 1, 2],
+      }
+    );
+    assert_eq!(report.power_consumption(), Some(0));
+    assert_eq!(report.life_support_rating(), Some(0));
+  }
+}
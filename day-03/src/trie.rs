@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+
+use crate::{BitCriteria, BitOrder, Readings, Round, Trace, Word};
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+  children: [Option<usize>; 2],
+  count: u64,
+}
+
+// Every node counts the readings below it, so a rating is found by walking
+// one path from the root instead of re-counting the candidates each round.
+#[derive(Debug, Clone)]
+pub struct BitTrie<W> {
+  width: usize,
+  order: BitOrder,
+  nodes: Vec<Node>,
+  word: PhantomData<W>,
+}
+
+impl<W: Word> BitTrie<W> {
+  pub fn new(readings: &Readings<W>) -> BitTrie<W> {
+    BitTrie::with_order(readings, BitOrder::MostSignificantFirst)
+  }
+
+  pub fn with_order(readings: &Readings<W>, order: BitOrder) -> BitTrie<W> {
+    let width = readings.width();
+    let mut nodes = vec![Node::default()];
+
+    for (value, weight) in readings.weighted() {
+      let mut node = 0;
+      nodes[node].count += weight;
+      for level in 0..width {
+        let bit = usize::from(value.bit(bit_index(width, order, level)));
+        node = match nodes[node].children[bit] {
+          Some(child) => child,
+          None => {
+            nodes.push(Node::default());
+            let child = nodes.len() - 1;
+            nodes[node].children[bit] = Some(child);
+            child
+          }
+        };
+        nodes[node].count += weight;
+      }
+    }
+
+    BitTrie {
+      width,
+      order,
+      nodes,
+      word: PhantomData,
+    }
+  }
+
+  pub fn len(&self) -> u64 {
+    self.nodes[0].count
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  fn count(&self, node: Option<usize>) -> u64 {
+    node.map_or(0, |node| self.nodes[node].count)
+  }
+
+  pub fn order(&self) -> BitOrder {
+    self.order
+  }
+
+  pub fn trace(&self, criteria: &BitCriteria) -> Trace<W> {
+    assert!(!self.is_empty(), "no readings");
+    assert_eq!(
+      criteria.order, self.order,
+      "trie was built in another bit order"
+    );
+
+    let mut node = 0;
+    let mut trace = Trace {
+      rounds: Vec::with_capacity(self.width),
+      value: W::default(),
+    };
+    for level in 0..self.width {
+      let bit = bit_index(self.width, self.order, level);
+      let [zero, one] = self.nodes[node].children;
+      let (zeros, ones) = (self.count(zero), self.count(one));
+      let kept_one = criteria.keeps_one(zeros, ones);
+      if kept_one {
+        trace.value = trace.value | (W::ONE << bit);
+      }
+      node = if kept_one { one } else { zero }.expect("chosen branch is empty");
+      trace.rounds.push(Round {
+        bit,
+        zeros,
+        ones,
+        kept_one,
+        rule: criteria.rule(zeros, ones),
+        survivors: self.nodes[node].count,
+      });
+    }
+
+    trace
+  }
+
+  pub fn rating(&self, criteria: &BitCriteria) -> W {
+    self.trace(criteria).value
+  }
+
+  pub fn oxygen_rating(&self) -> W {
+    self.rating(&BitCriteria::OXYGEN)
+  }
+
+  pub fn co2_rating(&self) -> W {
+    self.rating(&BitCriteria::CO2)
+  }
+}
+
+fn bit_index(width: usize, order: BitOrder, level: usize) -> usize {
+  match order {
+    BitOrder::MostSignificantFirst => width - 1 - level,
+    BitOrder::LeastSignificantFirst => level,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXAMPLE: [&str; 12] = [
+    "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001",
+    "00010", "01010",
+  ];
+
+  #[test]
+  fn trie_ratings_example() {
+    let trie = BitTrie::new(&Readings::<u64>::from_lines(&EXAMPLE));
+    assert_eq!(trie.len(), 12);
+    assert_eq!(trie.oxygen_rating(), 23);
+    assert_eq!(trie.co2_rating(), 10);
+  }
+
+  #[test]
+  fn trie_with_duplicates() {
+    let trie = BitTrie::new(&Readings::<u64>::from_lines(&["101", "101", "011"]));
+    assert_eq!(trie.oxygen_rating(), 0b101);
+    assert_eq!(trie.co2_rating(), 0b011);
+  }
+
+  #[test]
+  #[should_panic(expected = "no readings")]
+  fn trie_without_readings() {
+    let lines: [&str; 0] = [];
+    BitTrie::new(&Readings::<u64>::from_lines(&lines)).oxygen_rating();
+  }
+
+  #[test]
+  fn trie_from_least_significant_bit() {
+    let readings = Readings::<u64>::from_lines(&["100", "110", "011"]);
+    let trie = BitTrie::with_order(&readings, BitOrder::LeastSignificantFirst);
+    let criteria = BitCriteria {
+      order: BitOrder::LeastSignificantFirst,
+      ..BitCriteria::OXYGEN
+    };
+    assert_eq!(trie.rating(&criteria), 0b110);
+  }
+}